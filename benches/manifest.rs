@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Compares the cached `Manifest::new` with the uncached `Manifest::from_path`.
+//
+// Run with `cargo bench --bench manifest`.
+
+use std::{hint::black_box, path::Path, time::Instant};
+
+use find_crate::Manifest;
+
+const ITERATIONS: u32 = 1000;
+
+fn bench(name: &str, mut f: impl FnMut()) {
+    // Warm up.
+    f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    println!("{name:<30} {:>10.2?}/iter", start.elapsed() / ITERATIONS);
+}
+
+fn main() {
+    let manifest_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let member_manifest_path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/workspace/member/Cargo.toml");
+
+    bench("Manifest::from_path", || {
+        black_box(Manifest::from_path(black_box(&manifest_path)).unwrap());
+    });
+    bench("Manifest::from_path (member)", || {
+        black_box(Manifest::from_path(black_box(&member_manifest_path)).unwrap());
+    });
+    bench("Manifest::new", || {
+        black_box(Manifest::new().unwrap());
+    });
+    bench("find_crate", || {
+        black_box(find_crate::find_crate(|s| s == "toml").unwrap());
+    });
+}
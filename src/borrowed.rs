@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use alloc::borrow::Cow;
+
+use toml::de::DeTable;
+
+use crate::{
+    Dependencies, DependencyKind, Error, Platform, Precedence, Result, TomlError, error::Source,
+    model,
+};
+
+/// The manifest of cargo that borrows from the source text.
+///
+/// This is a lightweight alternative to [`Manifest`](crate::Manifest) for
+/// searching large manifests: only the dependency tables are kept after
+/// parsing, and the strings in the dependency tables and in the returned
+/// [`PackageRef`]s are borrowed from the source text. Only the strings that
+/// contain escape sequences (and the names of dependencies that contain `-`)
+/// are allocated.
+///
+/// Unlike `Manifest`, this does not know the location of the manifest, so
+/// dependencies inherited from the workspace (`foo.workspace = true`) are
+/// resolved only if the manifest itself has `[workspace.dependencies]`
+/// section.
+///
+/// # Examples
+///
+/// ```
+/// use find_crate::ManifestRef;
+///
+/// let manifest = ManifestRef::parse(
+///     r#"
+///     [dependencies]
+///     foo = { package = "foo-core", version = "0.1" }
+///     "#,
+/// )
+/// .unwrap();
+/// let package = manifest.find(|name| name == "foo-core").unwrap();
+/// assert_eq!(package.name, "foo");
+/// assert_eq!(package.version, "0.1");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManifestRef<'a> {
+    tables: model::DependencyTables<'a>,
+    // The source text, used to locate invalid entries.
+    text: &'a str,
+
+    /// The kind of dependencies to be searched.
+    pub dependencies: Dependencies,
+
+    /// The platform used to determine which platform-specific dependency tables
+    /// are searched. See [`Manifest::platform`](crate::Manifest::platform) for
+    /// details.
+    pub platform: Option<Platform>,
+
+    /// The order in which the dependency tables are searched. See
+    /// [`Manifest::precedence`](crate::Manifest::precedence) for details.
+    ///
+    /// If this is [`Precedence::Unique`] and the crate is found under multiple
+    /// names, [`find`](Self::find) returns `None`.
+    pub precedence: Precedence,
+}
+
+/// The package information borrowed from [`ManifestRef`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageRef<'a> {
+    /// The key of this dependency in the manifest.
+    key: Cow<'a, str>,
+
+    // The key or the value of 'package' key.
+    // If this is `None`, the value of `key` field is the original name.
+    package: Option<Cow<'a, str>>,
+
+    /// The current name of the package. This is always a valid rust identifier
+    /// (`-` is replaced with `_`).
+    pub name: Cow<'a, str>,
+
+    /// The version requirement of the package. Returns `*` if no version
+    /// requirement is specified.
+    pub version: Cow<'a, str>,
+
+    /// The section of the manifest where the package was found.
+    section: &'static str,
+
+    /// The platform-specific dependency table where the package was found.
+    target: Option<Cow<'a, str>>,
+}
+
+impl<'a> ManifestRef<'a> {
+    /// Creates a new `ManifestRef` from a string containing a TOML file.
+    ///
+    /// Note that the whole text is still checked to be valid TOML.
+    pub fn parse(text: &'a str) -> Result<Self> {
+        let root = DeTable::parse(text).map_err(|e| Error::Toml(TomlError::new(e, text)))?;
+        let mut tables = model::dependency_tables(root, text, false)?;
+        tables.inherit();
+        Ok(Self {
+            tables,
+            text,
+            dependencies: Dependencies::default(),
+            platform: None,
+            precedence: Precedence::default(),
+        })
+    }
+
+    /// Checks the dependency tables of this manifest, and returns the first
+    /// problem found.
+    ///
+    /// See [`Manifest::validate`](crate::Manifest::validate) for details.
+    /// Dependencies inherited from the workspace are checked only if this
+    /// manifest has `[workspace]` section.
+    pub fn validate(&self) -> Result<()> {
+        let source = Source { text: Some(self.text), path: None };
+        self.tables.validate(source, source)
+    }
+
+    /// Finds the crate with crate name, and returns its package information.
+    ///
+    /// See [`Manifest::find`](crate::Manifest::find) for details.
+    #[must_use]
+    pub fn find<P>(&self, mut predicate: P) -> Option<PackageRef<'a>>
+    where
+        P: FnMut(&str) -> bool,
+    {
+        self.find2(|s, _| predicate(s))
+    }
+
+    /// Finds the crate with crate name and version, and returns its package
+    /// information.
+    ///
+    /// See [`Manifest::find2`](crate::Manifest::find2) for details.
+    #[must_use]
+    pub fn find2<P>(&self, mut predicate: P) -> Option<PackageRef<'a>>
+    where
+        P: FnMut(&str, &str) -> bool,
+    {
+        let entries =
+            self.tables.entries(self.dependencies, self.platform.as_ref(), self.precedence);
+        model::find(&entries, self.precedence == Precedence::Unique, |entry| {
+            let package = entry.accepts(&mut predicate)?.package;
+            Some(PackageRef {
+                key: entry.key.clone(),
+                package: package.cloned(),
+                name: if entry.key.contains('-') {
+                    Cow::Owned(entry.key.replace('-', "_"))
+                } else {
+                    entry.key.clone()
+                },
+                version: entry.dependency.version.clone().unwrap_or(Cow::Borrowed("*")),
+                section: entry.section,
+                target: entry.target.cloned(),
+            })
+        })
+        .ok()
+        .flatten()
+    }
+}
+
+impl PackageRef<'_> {
+    /// Returns the original name of the package.
+    #[must_use]
+    pub fn original_name(&self) -> &str {
+        self.package.as_deref().unwrap_or(&self.key)
+    }
+
+    /// Returns `true` if the value of the [`name`](PackageRef::name) field is
+    /// the original name.
+    #[must_use]
+    pub fn is_original(&self) -> bool {
+        self.package.is_none()
+    }
+
+    /// Returns the section of the manifest where the package was found. See
+    /// [`Package::section`](crate::Package::section) for details.
+    #[must_use]
+    pub fn section(&self) -> &'static str {
+        self.section
+    }
+
+    /// Returns the kind of dependency.
+    #[must_use]
+    pub fn kind(&self) -> Option<DependencyKind> {
+        DependencyKind::from_section(self.section)
+    }
+
+    /// Returns the platform-specific dependency table where the package was
+    /// found. See [`Package::target`](crate::Package::target) for details.
+    #[must_use]
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+}
+
+impl model::Found for PackageRef<'_> {
+    fn original_name(&self) -> &str {
+        self.original_name()
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use alloc::vec::Vec;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::SystemTime,
+};
+
+use crate::{Manifest, Result};
+
+/// The manifests read by [`Manifest::new`], shared across all calls in this
+/// process (e.g., across all invocations of procedural macros in a crate).
+static CACHE: Mutex<Vec<Cached>> = Mutex::new(Vec::new());
+
+struct Cached {
+    manifest_path: PathBuf,
+    // The search for the workspace root stops at `CARGO_HOME`.
+    cargo_home: Option<PathBuf>,
+    // The files looked for to create the manifest and their stamps at that
+    // time, or `None` if they didn't exist or couldn't be read.
+    files: Vec<(PathBuf, Option<Stamp>)>,
+    manifest: Manifest,
+}
+
+/// The modification time and the size of a file.
+///
+/// The size is also compared because the modification time may have a coarse
+/// resolution on some filesystems.
+type Stamp = (SystemTime, u64);
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Returns the manifest of the specified file, reading it only if it has not
+/// been read or has been modified since it was read.
+pub(crate) fn get_or_load(manifest_path: &Path) -> Result<Manifest> {
+    let manifest_path = std::path::absolute(manifest_path)?;
+    let cargo_home = crate::cargo_home();
+    let is_key = |c: &Cached| c.manifest_path == manifest_path && c.cargo_home == cargo_home;
+    {
+        let cache = CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(cached) = cache.iter().find(|c| is_key(c)) {
+            if cached.files.iter().all(|(path, s)| stamp(path) == *s) {
+                return Ok(cached.manifest.clone());
+            }
+        }
+    }
+
+    // Get the stamp before reading the file, so that modifications during
+    // reading invalidate the cache next time.
+    let manifest_stamp = stamp(&manifest_path);
+    let manifest = Manifest::from_path(&manifest_path)?;
+    // If the file system doesn't support modification times, don't cache.
+    let Some(manifest_stamp) = manifest_stamp else { return Ok(manifest) };
+    let mut files = Vec::new();
+    for path in manifest.manifest_paths() {
+        let stamp = if path == manifest_path { Some(manifest_stamp) } else { stamp(&path) };
+        let Some(stamp) = stamp else { return Ok(manifest) };
+        files.push((path, Some(stamp)));
+    }
+    // Creating, removing, or modifying the manifests that were looked for,
+    // such as a workspace root manifest in a parent directory, also changes
+    // the result.
+    for path in &manifest.tables.probed_paths {
+        if !files.iter().any(|(p, _)| p == path) {
+            files.push((path.clone(), stamp(path)));
+        }
+    }
+
+    let mut cache = CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    cache.retain(|c| !is_key(c));
+    cache.push(Cached { manifest_path, cargo_home, files, manifest: manifest.clone() });
+    Ok(manifest)
+}
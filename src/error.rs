@@ -1,15 +1,29 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use alloc::string::String;
+use alloc::{borrow::ToOwned as _, boxed::Box, string::String, vec::Vec};
 use core::fmt;
-use std::io;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
 
-use crate::MANIFEST_DIR;
+use toml::de::DeTable;
+
+use crate::{MANIFEST_DIR, Package, model};
 
 /// An error which occurred while parsing the TOML manifest
 #[derive(Debug)]
 pub struct TomlError {
     pub(super) error: toml::de::Error,
+    // Boxed to keep the size of `Error` small.
+    pub(super) location: Option<Box<Location>>,
+}
+
+impl TomlError {
+    pub(crate) fn new(error: toml::de::Error, text: &str) -> Self {
+        let location = error.span().map(|span| Box::new(Location::new(None, text, span.start)));
+        Self { error, location }
+    }
 }
 
 impl fmt::Display for TomlError {
@@ -33,20 +47,43 @@ pub enum Error {
     /// [`CARGO_MANIFEST_DIR`]: https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-crates
     NotFoundManifestDir,
 
-    /// The manifest is invalid for the following reason.
-    // TODO(semver): make this Box<str> if it reduces size
-    InvalidManifest(String),
+    /// The manifest is invalid for the following reason, and the location of
+    /// the invalid entry if it is known. See also [`Error::location`].
+    // The location is boxed to keep the size of `Error` small.
+    InvalidManifest(String, Option<Box<Location>>),
+
+    /// The lockfile is invalid for the following reason.
+    InvalidLockfile(String),
 
-    /// The crate with the specified name not found. This error occurs only from [`find_crate`].
+    /// The crate with the specified name not found. This error occurs only from
+    /// [`find_crate`], [`Manifest::try_find`], [`Manifest::try_find2`], and
+    /// [`Manifest::find_unique`].
     ///
     /// [`find_crate`]: super::find_crate
-    NotFound,
+    /// [`Manifest::try_find`]: super::Manifest::try_find
+    /// [`Manifest::try_find2`]: super::Manifest::try_find2
+    /// [`Manifest::find_unique`]: super::Manifest::find_unique
+    NotFound(NotFound),
 
-    /// An error occurred while trying to open or to read the manifest file.
+    /// `Cargo.lock` not found. This error occurs only from [`Manifest::lockfile`].
+    ///
+    /// [`Manifest::lockfile`]: super::Manifest::lockfile
+    NotFoundLockfile,
+
+    /// An error occurred while trying to open or to read the manifest file or
+    /// the lockfile.
     Io(io::Error),
 
-    /// An error occurred while trying to parse the manifest file.
+    /// An error occurred while trying to parse the manifest file or the lockfile.
     Toml(TomlError),
+
+    /// The crate was found under multiple names. This contains all packages
+    /// of the crate found, in the order in which they are searched. This error occurs only
+    /// from [`Manifest::find_unique`], or when [`Precedence::Unique`] is used.
+    ///
+    /// [`Manifest::find_unique`]: super::Manifest::find_unique
+    /// [`Precedence::Unique`]: super::Precedence::Unique
+    Ambiguous(Vec<Package>),
 }
 
 impl fmt::Display for Error {
@@ -55,14 +92,33 @@ impl fmt::Display for Error {
             Error::NotFoundManifestDir => {
                 write!(f, "`{MANIFEST_DIR}` environment variable not found")
             }
-            Error::InvalidManifest(reason) => {
+            Error::InvalidManifest(reason, _) => {
                 write!(f, "The manifest is invalid because: {reason}")
             }
-            Error::NotFound => {
-                f.write_str("the crate with the specified name not found in dependencies")
+            Error::InvalidLockfile(reason) => {
+                write!(f, "The lockfile is invalid because: {reason}")
             }
+            Error::NotFound(e) => fmt::Display::fmt(e, f),
+            Error::NotFoundLockfile => f.write_str("`Cargo.lock` not found"),
             Error::Io(e) => write!(f, "an error occurred while to open or to read: {e}"),
-            Error::Toml(e) => write!(f, "an error occurred while parsing the manifest file: {e}"),
+            Error::Toml(e) => {
+                write!(f, "an error occurred while parsing the manifest file or the lockfile: {e}")
+            }
+            Error::Ambiguous(packages) => {
+                f.write_str("the crate with the specified name found under multiple names: ")?;
+                for (i, package) in packages.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(
+                        f,
+                        "`{}` in [{}]",
+                        package.name,
+                        model::table_name(package.section(), package.target()),
+                    )?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -77,6 +133,234 @@ impl core::error::Error for Error {
     }
 }
 
+impl Error {
+    /// Returns the location of the entry of the manifest or the lockfile that
+    /// caused this error.
+    ///
+    /// This is available for [`Error::InvalidManifest`] and [`Error::Toml`]
+    /// errors. Returns `None` if the location is unknown, e.g., the error
+    /// occurred in a [`Manifest`](crate::Manifest) created by
+    /// [`from_str`](core::str::FromStr::from_str) after it was created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    ///
+    /// use find_crate::Manifest;
+    ///
+    /// let err = Manifest::from_str("[dependencies]\nfoo = { package = 3 }").unwrap_err();
+    /// let location = err.location().unwrap();
+    /// assert_eq!((location.line(), location.column()), (2, 9));
+    /// ```
+    #[must_use]
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::InvalidManifest(_, location) => location.as_deref(),
+            Error::Toml(e) => e.location.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Sets the path of the file to the location of this error.
+    pub(crate) fn with_path(mut self, path: &Path) -> Self {
+        let location = match &mut self {
+            Error::InvalidManifest(_, location) => location.as_deref_mut(),
+            Error::Toml(e) => e.location.as_deref_mut(),
+            _ => None,
+        };
+        if let Some(location) = location {
+            location.path.get_or_insert_with(|| path.to_owned());
+        }
+        self
+    }
+}
+
+/// The details of [`Error::NotFound`].
+#[derive(Debug, Clone)]
+pub struct NotFound {
+    pub(crate) sections: &'static [&'static str],
+    pub(crate) similar_names: Vec<String>,
+    pub(crate) filtered: Vec<Package>,
+}
+
+impl NotFound {
+    /// Returns the sections that were searched.
+    ///
+    /// The platform-specific dependency tables of these sections (e.g.,
+    /// `[target.'cfg(unix)'.dependencies]`) and the sections with the legacy
+    /// spellings (e.g., `dev_dependencies`) that are not ignored by cargo were
+    /// also searched.
+    #[must_use]
+    pub fn sections(&self) -> &[&'static str] {
+        self.sections
+    }
+
+    /// Returns the names of the dependencies that are not accepted by the
+    /// predicate, but differ by one character from a name accepted by the
+    /// predicate (e.g., `foo_core` when searching `foo-core`).
+    #[must_use]
+    pub fn similar_names(&self) -> &[String] {
+        &self.similar_names
+    }
+
+    /// Returns the dependencies that match the name but were filtered out.
+    ///
+    /// This contains dependencies accepted by the predicate but in the sections
+    /// or the platform-specific dependency tables that were not searched, and
+    /// dependencies whose version requirement was rejected by the predicate of
+    /// [`Manifest::try_find2`](crate::Manifest::try_find2), even though the
+    /// predicate accepts the name with any version (`*`).
+    #[must_use]
+    pub fn filtered(&self) -> &[Package] {
+        &self.filtered
+    }
+}
+
+impl fmt::Display for NotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the crate with the specified name not found in dependencies")?;
+        for (i, section) in self.sections.iter().enumerate() {
+            f.write_str(if i == 0 { " (searched: " } else { ", " })?;
+            write!(f, "[{section}]")?;
+            if i == self.sections.len() - 1 {
+                f.write_str(")")?;
+            }
+        }
+        for (i, name) in self.similar_names.iter().enumerate() {
+            f.write_str(if i == 0 { "; similar names: " } else { ", " })?;
+            write!(f, "`{name}`")?;
+        }
+        for (i, package) in self.filtered.iter().enumerate() {
+            f.write_str(if i == 0 { "; filtered out: " } else { ", " })?;
+            write!(
+                f,
+                "`{}` (version `{}`) in [{}]",
+                package.original_name(),
+                package.version,
+                model::table_name(package.section(), package.target()),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The location of an entry in the manifest or the lockfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    path: Option<PathBuf>,
+    line: usize,
+    column: usize,
+}
+
+impl Location {
+    fn new(path: Option<PathBuf>, text: &str, offset: usize) -> Self {
+        let before = text.get(..offset).unwrap_or(text);
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            path,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// Returns the path of the file, or `None` if the manifest was not read
+    /// from a file.
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Returns the line number, starting at 1.
+    #[must_use]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column number in characters, starting at 1.
+    #[must_use]
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{}:", path.display())?;
+        }
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// The source of the manifest, used to locate invalid entries.
+///
+/// If `text` is `None`, the file at `path` is read when locating.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Source<'a> {
+    pub(crate) text: Option<&'a str>,
+    pub(crate) path: Option<&'a Path>,
+}
+
+impl Source<'_> {
+    /// Creates an [`Error::InvalidManifest`] for the entry at the specified
+    /// keys, e.g., `["dependencies", "foo", "package"]`.
+    pub(crate) fn invalid(self, reason: String, keys: &[&str]) -> Error {
+        Error::InvalidManifest(reason, self.locate(keys).map(Box::new))
+    }
+
+    /// Creates an [`Error::InvalidManifest`] from the error that occurred while
+    /// deserializing the manifest. The error is located at the key whose value
+    /// caused the error.
+    pub(crate) fn invalid_at(self, error: &toml::de::Error) -> Error {
+        let location = error.span().and_then(|span| {
+            self.find_key(|table| {
+                fn find(table: &DeTable<'_>, offset: usize) -> Option<usize> {
+                    table.iter().find_map(|(key, value)| {
+                        // The innermost key takes precedence. Note that the span
+                        // of a table defined by a header is the span of the header.
+                        value
+                            .get_ref()
+                            .as_table()
+                            .and_then(|table| find(table, offset))
+                            .or_else(|| value.span().contains(&offset).then(|| key.span().start))
+                    })
+                }
+                find(table, span.start)
+            })
+        });
+        Error::InvalidManifest(error.message().to_owned(), location.map(Box::new))
+    }
+
+    fn locate(self, keys: &[&str]) -> Option<Location> {
+        self.find_key(|table| {
+            let (&last, parents) = keys.split_last()?;
+            let mut table = table;
+            for key in parents {
+                table = table.get(*key)?.get_ref().as_table()?;
+            }
+            let (key, _) = table.get_key_value(last)?;
+            Some(key.span().start)
+        })
+    }
+
+    /// Returns the location of the key at the offset returned by the
+    /// specified function.
+    fn find_key(self, f: impl FnOnce(&DeTable<'_>) -> Option<usize>) -> Option<Location> {
+        let read;
+        let text = match (self.text, self.path) {
+            (Some(text), _) => text,
+            (None, Some(path)) => {
+                read = fs::read_to_string(path).ok()?;
+                &read
+            }
+            (None, None) => return None,
+        };
+        let table = DeTable::parse(text).ok()?.into_inner();
+        Some(Location::new(self.path.map(Path::to_path_buf), text, f(&table)?))
+    }
+}
+
 impl From<io::Error> for Error {
     fn from(e: io::Error) -> Self {
         Error::Io(e)
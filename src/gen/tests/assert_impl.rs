@@ -41,6 +41,16 @@ macro_rules! assert_not_ref_unwind_safe {
     };
 }
 const _: fn() = || {
+    assert_send::<crate::borrowed::ManifestRef<'static>>();
+    assert_sync::<crate::borrowed::ManifestRef<'static>>();
+    assert_unpin::<crate::borrowed::ManifestRef<'static>>();
+    assert_unwind_safe::<crate::borrowed::ManifestRef<'static>>();
+    assert_ref_unwind_safe::<crate::borrowed::ManifestRef<'static>>();
+    assert_send::<crate::borrowed::PackageRef<'static>>();
+    assert_sync::<crate::borrowed::PackageRef<'static>>();
+    assert_unpin::<crate::borrowed::PackageRef<'static>>();
+    assert_unwind_safe::<crate::borrowed::PackageRef<'static>>();
+    assert_ref_unwind_safe::<crate::borrowed::PackageRef<'static>>();
     assert_send::<crate::error::TomlError>();
     assert_sync::<crate::error::TomlError>();
     assert_unpin::<crate::error::TomlError>();
@@ -51,16 +61,76 @@ const _: fn() = || {
     assert_unpin::<crate::error::Error>();
     assert_not_unwind_safe!(crate::error::Error);
     assert_not_ref_unwind_safe!(crate::error::Error);
+    assert_send::<crate::error::NotFound>();
+    assert_sync::<crate::error::NotFound>();
+    assert_unpin::<crate::error::NotFound>();
+    assert_unwind_safe::<crate::error::NotFound>();
+    assert_ref_unwind_safe::<crate::error::NotFound>();
+    assert_send::<crate::error::Location>();
+    assert_sync::<crate::error::Location>();
+    assert_unpin::<crate::error::Location>();
+    assert_unwind_safe::<crate::error::Location>();
+    assert_ref_unwind_safe::<crate::error::Location>();
+    assert_send::<crate::lockfile::Lockfile>();
+    assert_sync::<crate::lockfile::Lockfile>();
+    assert_unpin::<crate::lockfile::Lockfile>();
+    assert_unwind_safe::<crate::lockfile::Lockfile>();
+    assert_ref_unwind_safe::<crate::lockfile::Lockfile>();
+    assert_send::<crate::lockfile::LockedPackage>();
+    assert_sync::<crate::lockfile::LockedPackage>();
+    assert_unpin::<crate::lockfile::LockedPackage>();
+    assert_unwind_safe::<crate::lockfile::LockedPackage>();
+    assert_ref_unwind_safe::<crate::lockfile::LockedPackage>();
+    assert_send::<crate::platform::Platform>();
+    assert_sync::<crate::platform::Platform>();
+    assert_unpin::<crate::platform::Platform>();
+    assert_unwind_safe::<crate::platform::Platform>();
+    assert_ref_unwind_safe::<crate::platform::Platform>();
+    assert_send::<crate::target::TargetKind>();
+    assert_sync::<crate::target::TargetKind>();
+    assert_unpin::<crate::target::TargetKind>();
+    assert_unwind_safe::<crate::target::TargetKind>();
+    assert_ref_unwind_safe::<crate::target::TargetKind>();
     assert_send::<crate::Dependencies>();
     assert_sync::<crate::Dependencies>();
     assert_unpin::<crate::Dependencies>();
     assert_unwind_safe::<crate::Dependencies>();
     assert_ref_unwind_safe::<crate::Dependencies>();
+    assert_send::<crate::Precedence>();
+    assert_sync::<crate::Precedence>();
+    assert_unpin::<crate::Precedence>();
+    assert_unwind_safe::<crate::Precedence>();
+    assert_ref_unwind_safe::<crate::Precedence>();
+    assert_send::<crate::DependencyKind>();
+    assert_sync::<crate::DependencyKind>();
+    assert_unpin::<crate::DependencyKind>();
+    assert_unwind_safe::<crate::DependencyKind>();
+    assert_ref_unwind_safe::<crate::DependencyKind>();
+    assert_send::<crate::DependencyKinds>();
+    assert_sync::<crate::DependencyKinds>();
+    assert_unpin::<crate::DependencyKinds>();
+    assert_unwind_safe::<crate::DependencyKinds>();
+    assert_ref_unwind_safe::<crate::DependencyKinds>();
+    assert_send::<crate::DependencySource>();
+    assert_sync::<crate::DependencySource>();
+    assert_unpin::<crate::DependencySource>();
+    assert_unwind_safe::<crate::DependencySource>();
+    assert_ref_unwind_safe::<crate::DependencySource>();
+    assert_send::<crate::GitReference>();
+    assert_sync::<crate::GitReference>();
+    assert_unpin::<crate::GitReference>();
+    assert_unwind_safe::<crate::GitReference>();
+    assert_ref_unwind_safe::<crate::GitReference>();
     assert_send::<crate::Package>();
     assert_sync::<crate::Package>();
     assert_unpin::<crate::Package>();
     assert_unwind_safe::<crate::Package>();
     assert_ref_unwind_safe::<crate::Package>();
+    assert_send::<crate::FoundCrate>();
+    assert_sync::<crate::FoundCrate>();
+    assert_unpin::<crate::FoundCrate>();
+    assert_unwind_safe::<crate::FoundCrate>();
+    assert_ref_unwind_safe::<crate::FoundCrate>();
     assert_send::<crate::Manifest>();
     assert_sync::<crate::Manifest>();
     assert_unpin::<crate::Manifest>();
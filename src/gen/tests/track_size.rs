@@ -25,10 +25,24 @@ fn write_size<T>(out: &mut String) {
 #[test]
 fn track_size() {
     let mut out = String::new();
+    write_size::<crate::borrowed::ManifestRef<'static>>(&mut out);
+    write_size::<crate::borrowed::PackageRef<'static>>(&mut out);
     write_size::<crate::error::TomlError>(&mut out);
     write_size::<crate::error::Error>(&mut out);
+    write_size::<crate::error::NotFound>(&mut out);
+    write_size::<crate::error::Location>(&mut out);
+    write_size::<crate::lockfile::Lockfile>(&mut out);
+    write_size::<crate::lockfile::LockedPackage>(&mut out);
+    write_size::<crate::platform::Platform>(&mut out);
+    write_size::<crate::target::TargetKind>(&mut out);
     write_size::<crate::Dependencies>(&mut out);
+    write_size::<crate::Precedence>(&mut out);
+    write_size::<crate::DependencyKind>(&mut out);
+    write_size::<crate::DependencyKinds>(&mut out);
+    write_size::<crate::DependencySource>(&mut out);
+    write_size::<crate::GitReference>(&mut out);
     write_size::<crate::Package>(&mut out);
+    write_size::<crate::FoundCrate>(&mut out);
     write_size::<crate::Manifest>(&mut out);
     test_helper::git::assert_diff(
         Path::new(env!("CARGO_MANIFEST_DIR")).join("src/gen/tests/track_size.txt"),
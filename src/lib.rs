@@ -88,6 +88,24 @@ This behavior can be adjusted by changing the [`Manifest::dependencies`] field.
 
 [`find_crate`] and [`Manifest::new`] functions read `Cargo.toml` in
 [`CARGO_MANIFEST_DIR`] as manifest.
+If the package is a member of a workspace, dependencies inherited from the
+workspace (`foo.workspace = true`) are resolved using the workspace root
+manifest.
+
+## Optional features
+
+- **`proc-macro2`**<br>
+  Enable `Package::to_path_tokens` to get the path to the crate, and
+  `Manifest::to_tracking_tokens` to get the tokens that make the compiler
+  track the manifest files, as [proc-macro2]'s `TokenStream`.
+
+  Note: proc-macro2 is public dependencies.
+
+- **`semver`**<br>
+  Enable `Manifest::find_version` and `Package::version_req` to match a version
+  against the version requirement using [semver].
+
+  Note: semver is public dependencies.
 
 ## Alternatives
 
@@ -100,6 +118,8 @@ for multiple crate names and versions. For general purposes,
 [proc-macro-crate], which provides a simpler API, may be easier to use.
 
 [`CARGO_MANIFEST_DIR`]: https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-crates
+[proc-macro2]: https://github.com/dtolnay/proc-macro2
+[semver]: https://github.com/dtolnay/semver
 [rust-lang/futures-rs#2124]: https://github.com/rust-lang/futures-rs/pull/2124
 [proc-macro-crate]: https://github.com/bkchr/proc-macro-crate
 
@@ -140,18 +160,41 @@ mod assert_impl;
 #[path = "gen/tests/track_size.rs"]
 mod track_size;
 
+mod borrowed;
+mod cache;
 mod error;
+mod lockfile;
+mod model;
+mod platform;
+mod target;
 
-use alloc::{borrow::ToOwned as _, string::String};
-use core::str::FromStr;
+use alloc::{
+    borrow::{Cow, ToOwned as _},
+    string::String,
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+use core::{fmt, ops, str::FromStr};
 use std::{
     env, fs,
     path::{Path, PathBuf},
 };
 
-use toml::value::{Table, Value};
+use serde::Deserialize as _;
+use toml::{
+    de::{DeTable, Deserializer},
+    value::{Table, Value},
+};
 
-pub use self::error::{Error, TomlError};
+use self::error::Source;
+pub use self::{
+    borrowed::{ManifestRef, PackageRef},
+    error::{Error, Location, NotFound, TomlError},
+    lockfile::{LockedPackage, Lockfile},
+    platform::Platform,
+    target::TargetKind,
+};
 
 type Result<T, E = Error> = core::result::Result<T, E>;
 
@@ -166,6 +209,14 @@ const MANIFEST_DIR: &str = "CARGO_MANIFEST_DIR";
 ///
 /// Note that this function needs to be used in the context of proc-macro.
 ///
+/// If the crate is not found, this returns [`Error::NotFound`] that tells which
+/// sections were searched and which dependencies are close to the crate. See
+/// [`Manifest::try_find`] for details.
+///
+/// To find the dependencies close to the crate, the predicate may also be
+/// called with names that are not in the manifest, so it should not have side
+/// effects.
+///
 /// # Examples
 ///
 /// ```
@@ -186,10 +237,16 @@ pub fn find_crate<P>(predicate: P) -> Result<Package>
 where
     P: FnMut(&str) -> bool,
 {
-    Manifest::new()?.find(predicate).ok_or(Error::NotFound)
+    Manifest::new()?.try_find(predicate)
 }
 
 /// The kind of dependencies to be searched.
+///
+/// The sections with the legacy spellings that cargo still accepts
+/// (`dev_dependencies` and `build_dependencies`) are also searched. Like cargo,
+/// the section with the legacy spelling is ignored if the section with the
+/// hyphenated spelling is also specified, at the top level or for the same
+/// target.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Dependencies {
@@ -204,20 +261,248 @@ pub enum Dependencies {
     Build,
     /// Search from `dependencies`, `dev-dependencies` and `build-dependencies`.
     All,
+    /// Search from the sections of the specified kinds, e.g.,
+    /// `DependencyKinds::DEV | DependencyKinds::BUILD`.
+    Kinds(DependencyKinds),
 }
 
 impl Dependencies {
-    fn as_slice(self) -> &'static [&'static str] {
+    /// Returns the kinds of dependencies to be searched.
+    #[must_use]
+    pub fn kinds(self) -> DependencyKinds {
         match self {
-            Dependencies::Default => &["dependencies", "dev-dependencies"],
-            Dependencies::Release => &["dependencies"],
-            Dependencies::Dev => &["dev-dependencies"],
-            Dependencies::Build => &["build-dependencies"],
-            Dependencies::All => &["dependencies", "dev-dependencies", "build-dependencies"],
+            Dependencies::Default => DependencyKinds::NORMAL | DependencyKinds::DEV,
+            Dependencies::Release => DependencyKinds::NORMAL,
+            Dependencies::Dev => DependencyKinds::DEV,
+            Dependencies::Build => DependencyKinds::BUILD,
+            Dependencies::All => DependencyKinds::ALL,
+            Dependencies::Kinds(kinds) => kinds,
+        }
+    }
+
+    fn as_slice(self) -> &'static [&'static str] {
+        self.kinds().as_slice()
+    }
+}
+
+impl From<DependencyKinds> for Dependencies {
+    fn from(kinds: DependencyKinds) -> Self {
+        Dependencies::Kinds(kinds)
+    }
+}
+
+/// The order in which the dependency tables are searched.
+///
+/// The dependency tables are searched in two groups: the dependency tables at
+/// the top level and the platform-specific dependency tables. This decides
+/// which group is searched first, and all tables of that group are searched
+/// before the other group (e.g., with [`TargetFirst`](Precedence::TargetFirst),
+/// `[target.'cfg(unix)'.dev-dependencies]` is searched before
+/// `[dependencies]`).
+///
+/// Within each group, the sections are searched in the order of
+/// `dependencies`, `dev-dependencies`, and `build-dependencies`, and the
+/// platform-specific dependency tables of the same section are searched in the
+/// lexicographic order of their target names (e.g., `cfg(unix)` before
+/// `cfg(windows)`), not in the order in which they appear in the manifest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Precedence {
+    /// Search the dependency tables at the top level (e.g., `[dependencies]`)
+    /// first, then the platform-specific dependency tables (e.g.,
+    /// `[target.'cfg(unix)'.dependencies]`).
+    #[default]
+    TopLevelFirst,
+    /// Search the platform-specific dependency tables first, then the
+    /// dependency tables at the top level.
+    ///
+    /// This is useful with [`Manifest::platform`], to prefer the dependency
+    /// specified for the active platform.
+    TargetFirst,
+    /// Search like [`TopLevelFirst`](Precedence::TopLevelFirst), but treat the
+    /// crate found under multiple names (e.g., renamed differently in
+    /// `[target.'cfg(unix)'.dependencies]` and
+    /// `[target.'cfg(windows)'.dependencies]`) as an error. Different crates
+    /// accepted by the predicate (e.g., `foo` and `foo-core`) are not
+    /// ambiguous, and the first one found is returned.
+    ///
+    /// [`Manifest::try_find`] and [`find_crate`] return [`Error::Ambiguous`],
+    /// and [`Manifest::find`] returns `None` in this case.
+    Unique,
+}
+
+/// The kind of dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DependencyKind {
+    /// `dependencies`
+    Normal,
+    /// `dev-dependencies`
+    Dev,
+    /// `build-dependencies`
+    Build,
+}
+
+impl DependencyKind {
+    fn from_section(section: &str) -> Option<Self> {
+        match section {
+            "dependencies" => Some(DependencyKind::Normal),
+            "dev-dependencies" | "dev_dependencies" => Some(DependencyKind::Dev),
+            "build-dependencies" | "build_dependencies" => Some(DependencyKind::Build),
+            _ => None,
+        }
+    }
+}
+
+/// A set of [`DependencyKind`]s.
+///
+/// Unlike [`Dependencies`], this can represent any combination of the kinds of
+/// dependencies. Use [`Dependencies::Kinds`] to search the sections of these
+/// kinds.
+///
+/// # Examples
+///
+/// ```
+/// use find_crate::{Dependencies, DependencyKind, DependencyKinds, Manifest};
+///
+/// let mut manifest = Manifest::new().unwrap();
+/// let kinds = DependencyKinds::DEV | DependencyKinds::BUILD;
+/// assert!(kinds.contains(DependencyKind::Build.into()));
+/// assert!(!kinds.contains(DependencyKinds::NORMAL));
+/// manifest.dependencies = Dependencies::Kinds(kinds);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DependencyKinds(u8);
+
+impl DependencyKinds {
+    /// `dependencies`
+    pub const NORMAL: Self = Self(1 << 0);
+    /// `dev-dependencies`
+    pub const DEV: Self = Self(1 << 1);
+    /// `build-dependencies`
+    pub const BUILD: Self = Self(1 << 2);
+    /// All kinds of dependencies.
+    pub const ALL: Self = Self(Self::NORMAL.0 | Self::DEV.0 | Self::BUILD.0);
+
+    /// Returns an empty set.
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns `true` if this set contains no kinds.
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if this set contains all kinds in `other`.
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Adds all kinds in `other` to this set.
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Removes all kinds in `other` from this set.
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    fn as_slice(self) -> &'static [&'static str] {
+        const SECTIONS: [&[&str]; 8] = [
+            &[],
+            &["dependencies"],
+            &["dev-dependencies"],
+            &["dependencies", "dev-dependencies"],
+            &["build-dependencies"],
+            &["dependencies", "build-dependencies"],
+            &["dev-dependencies", "build-dependencies"],
+            &["dependencies", "dev-dependencies", "build-dependencies"],
+        ];
+        SECTIONS[usize::from(self.0)]
+    }
+}
+
+impl From<DependencyKind> for DependencyKinds {
+    fn from(kind: DependencyKind) -> Self {
+        match kind {
+            DependencyKind::Normal => Self::NORMAL,
+            DependencyKind::Dev => Self::DEV,
+            DependencyKind::Build => Self::BUILD,
         }
     }
 }
 
+impl ops::BitOr for DependencyKinds {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl ops::BitOrAssign for DependencyKinds {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
+
+/// The source of a dependency.
+///
+/// See [the Cargo Book](https://doc.rust-lang.org/cargo/reference/specifying-dependencies.html)
+/// for more.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DependencySource {
+    /// A dependency from a registry.
+    ///
+    /// This contains the name of the registry specified by the `registry` key,
+    /// or `None` if it is not specified (i.e., crates.io).
+    Registry(Option<String>),
+    /// A dependency from a registry specified by the URL of its index.
+    ///
+    /// This contains the value of the `registry-index` key, which is used
+    /// instead of `registry` in the manifests normalized by `cargo package`.
+    RegistryIndex(String),
+    /// A dependency from a local path specified by the `path` key.
+    ///
+    /// If the manifest was read from a file, the path is resolved relative to
+    /// the directory of the manifest that declares the dependency. The path of
+    /// the dependency inherited from the workspace, and the path with
+    /// `base = "workspace"`, are relative to the workspace root. Otherwise,
+    /// including the path with other path bases (which are defined in the
+    /// cargo configuration), this is the path as written in the manifest.
+    ///
+    /// Note that a dependency that specifies both `path` and `version` is a path
+    /// dependency when used locally.
+    Path(PathBuf),
+    /// A dependency from a git repository.
+    #[non_exhaustive]
+    Git {
+        /// The URL of the git repository specified by the `git` key.
+        url: String,
+        /// The reference specified by the `branch`, `tag`, or `rev` key, or
+        /// `None` if it is not specified (i.e., the default branch).
+        reference: Option<GitReference>,
+    },
+}
+
+/// The reference of a git dependency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GitReference {
+    /// A branch specified by the `branch` key.
+    Branch(String),
+    /// A tag specified by the `tag` key.
+    Tag(String),
+    /// A commit specified by the `rev` key.
+    Rev(String),
+}
+
 /// The package information. This has information on the current package name,
 /// original package name, and specified version.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -231,11 +516,33 @@ pub struct Package {
 
     /// The current name of the package. This is always a valid rust identifier
     /// (`-` is replaced with `_`).
+    ///
+    /// If the package is not renamed, this is the name of its library target
+    /// (`name` in `[lib]` section) if it is known, i.e., for the package returned
+    /// by [`Manifest::crate_package`] and for path dependencies whose manifest
+    /// can be read. Note that the lockfile does not record the library target
+    /// name, so this is not resolved for registry and git dependencies.
     pub name: String,
 
     /// The version requirement of the package. Returns `*` if no version
     /// requirement is specified.
     pub version: String,
+
+    // The name of the table in which this package was found.
+    section: &'static str,
+
+    // The target of the platform-specific dependency table in which this
+    // package was found.
+    target: Option<String>,
+
+    features: Vec<String>,
+    default_features: bool,
+    optional: bool,
+    source: Option<DependencySource>,
+
+    // Whether this package refers to the current crate itself (`crate`) rather
+    // than an external crate.
+    itself: bool,
 }
 
 impl Package {
@@ -253,6 +560,150 @@ impl Package {
     pub fn is_original(&self) -> bool {
         self.package.is_none()
     }
+
+    /// Returns the name of the table in which this package was found.
+    ///
+    /// This is `dependencies`, `dev-dependencies`, or `build-dependencies` if
+    /// this package was found in the dependencies, and `package` if this is the
+    /// package returned by [`Manifest::crate_package`].
+    ///
+    /// If the package was found in a section with the legacy spelling that
+    /// cargo still accepts (`dev_dependencies` or `build_dependencies`), this
+    /// returns that spelling. Use [`Package::kind`] to get the kind of
+    /// dependency regardless of the spelling.
+    #[must_use]
+    pub fn section(&self) -> &str {
+        self.section
+    }
+
+    /// Returns the path to the crate as tokens.
+    ///
+    /// This returns `crate` if this is the package returned by
+    /// [`Manifest::crate_package`] while the library of the package is being
+    /// compiled, otherwise the absolute path to the crate with its current name
+    /// (e.g., `::foo_renamed`). See [`Manifest::find_or_itself`] for details.
+    ///
+    /// Note that absolute paths starting with `::` require the 2018 edition or
+    /// later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use find_crate::find_crate;
+    /// use proc_macro2::TokenStream;
+    /// use quote::quote;
+    ///
+    /// fn import() -> TokenStream {
+    ///     let path = find_crate(|name| name == "foo").unwrap().to_path_tokens();
+    ///     quote!(use #path as _foo;)
+    /// }
+    /// ```
+    #[cfg(feature = "proc-macro2")]
+    #[must_use]
+    pub fn to_path_tokens(&self) -> proc_macro2::TokenStream {
+        use proc_macro2::{Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+
+        if self.itself {
+            return TokenTree::Ident(Ident::new("crate", Span::call_site())).into();
+        }
+        TokenStream::from_iter([
+            TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+            TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+            TokenTree::Ident(Ident::new(&self.name, Span::call_site())),
+        ])
+    }
+
+    /// Returns the version requirement of the package as [`semver::VersionReq`].
+    ///
+    /// Returns `None` if the [`version`](Package::version) field is not a valid
+    /// version requirement.
+    #[cfg(feature = "semver")]
+    #[must_use]
+    pub fn version_req(&self) -> Option<semver::VersionReq> {
+        semver::VersionReq::parse(&self.version).ok()
+    }
+
+    /// Returns the kind of dependency.
+    ///
+    /// Returns `None` if this is the package returned by [`Manifest::crate_package`].
+    #[must_use]
+    pub fn kind(&self) -> Option<DependencyKind> {
+        DependencyKind::from_section(self.section)
+    }
+
+    /// Returns the features of the dependency specified by the `features` key.
+    ///
+    /// This does not include the default features.
+    #[must_use]
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    /// Returns `false` if the default features of the dependency are disabled
+    /// by `default-features = false`.
+    #[must_use]
+    pub fn default_features(&self) -> bool {
+        self.default_features
+    }
+
+    /// Returns `true` if the dependency is optional (`optional = true`).
+    #[must_use]
+    pub fn optional(&self) -> bool {
+        self.optional
+    }
+
+    /// Returns where the dependency comes from.
+    ///
+    /// See [`DependencySource::Path`] for how the path of a path dependency is
+    /// resolved.
+    ///
+    /// Returns `None` if this is the package returned by [`Manifest::crate_package`].
+    #[must_use]
+    pub fn source(&self) -> Option<&DependencySource> {
+        self.source.as_ref()
+    }
+
+    /// Returns the target of the platform-specific dependency table in which
+    /// this package was found.
+    ///
+    /// This is the raw string as written in the manifest: a cfg expression such
+    /// as `cfg(windows)` for `[target.'cfg(windows)'.dependencies]`, or a target
+    /// triple such as `x86_64-pc-windows-msvc` for
+    /// `[target.x86_64-pc-windows-msvc.dependencies]`.
+    ///
+    /// Returns `None` if this package was not found in the platform-specific
+    /// dependency table.
+    #[must_use]
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+}
+
+/// The crate found by [`Manifest::find_or_itself`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::exhaustive_enums)] // Intended to be matched exhaustively.
+#[allow(clippy::large_enum_variant)] // Returned once per lookup; boxing would only add an allocation.
+pub enum FoundCrate {
+    /// The crate is the current crate itself. Use `crate` to refer to it.
+    Itself,
+    /// The crate is an external crate with the package information.
+    Named(Package),
+}
+
+impl FoundCrate {
+    /// Returns the path to the crate as tokens: `crate` for [`FoundCrate::Itself`],
+    /// and the absolute path to the crate (e.g., `::foo_renamed`) for
+    /// [`FoundCrate::Named`].
+    #[cfg(feature = "proc-macro2")]
+    #[must_use]
+    pub fn to_path_tokens(&self) -> proc_macro2::TokenStream {
+        use proc_macro2::{Ident, Span, TokenTree};
+
+        match self {
+            FoundCrate::Itself => TokenTree::Ident(Ident::new("crate", Span::call_site())).into(),
+            FoundCrate::Named(package) => package.to_path_tokens(),
+        }
+    }
 }
 
 /// The manifest of cargo.
@@ -260,25 +711,98 @@ impl Package {
 /// Note that this function needs to be used in the context of proc-macro.
 #[derive(Debug, Clone)]
 pub struct Manifest {
-    manifest: Table,
+    // The tables are shared between clones, so cloning `Manifest` is cheap.
+    manifest: Arc<Table>,
+
+    // The `[workspace]` section of the workspace root manifest.
+    workspace: Option<Arc<Table>>,
+
+    // The dependency tables of this manifest, and `[workspace.dependencies]`
+    // of the workspace root manifest.
+    tables: Arc<model::DependencyTables<'static>>,
+
+    // The path to this manifest and the directory containing it. These are
+    // `None` if this manifest was not read from a file.
+    manifest_path: Option<PathBuf>,
+    manifest_dir: Option<PathBuf>,
+
+    // The workspace root directory. This is `None` if this manifest was not
+    // read from a file or is not a member of a workspace.
+    workspace_root: Option<PathBuf>,
 
     /// The kind of dependencies to be searched.
     pub dependencies: Dependencies,
+
+    /// The platform used to determine which platform-specific dependency tables
+    /// are searched.
+    ///
+    /// If this is `None` (default), all platform-specific dependency tables are
+    /// searched regardless of whether they are active.
+    pub platform: Option<Platform>,
+
+    /// The order in which the dependency tables are searched, and whether to
+    /// reject the crate found under multiple names.
+    pub precedence: Precedence,
 }
 
 impl Manifest {
     /// Creates a new `Manifest` from the current `Cargo.toml`.
     ///
     /// This function reads `Cargo.toml` in [`CARGO_MANIFEST_DIR`] as manifest.
+    /// If the package is a member of a workspace, the workspace root manifest
+    /// is also read to resolve dependencies inherited from the workspace
+    /// (`foo.workspace = true`).
+    ///
+    /// The parsed manifest is cached for the lifetime of the process, so
+    /// calling this function repeatedly (e.g., once per macro invocation) reads
+    /// and parses the manifest only once. The cache is invalidated when the
+    /// modification time or the size of the manifest file, the workspace root
+    /// manifest file, or the manifest file of a path dependency changes, when
+    /// a manifest file that was looked for (e.g., a workspace root manifest in
+    /// a parent directory) is created or removed, or when `CARGO_HOME` changes.
+    /// Use [`Manifest::from_path`] to always read the manifest from the file.
     ///
     /// [`CARGO_MANIFEST_DIR`]: https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-crates
     pub fn new() -> Result<Self> {
-        Self::from_path(&manifest_path()?)
+        cache::get_or_load(&manifest_path()?)
     }
 
     /// Creates a new `Manifest` from the specified toml file.
+    ///
+    /// If the package is a member of a workspace, the workspace root manifest
+    /// is found by searching the parent directories (or by the `package.workspace`
+    /// field), like cargo does.
     pub fn from_path(manifest_path: &Path) -> Result<Self> {
-        Self::from_str(&fs::read_to_string(manifest_path)?)
+        let manifest_path = std::path::absolute(manifest_path)?;
+        let (manifest, mut tables) = parse_manifest(&fs::read_to_string(&manifest_path)?, false)
+            .map_err(|e| e.with_path(&manifest_path))?;
+        let manifest_dir = manifest_path.parent().map(Path::to_path_buf);
+        let mut workspace = manifest.get("workspace").and_then(Value::as_table).cloned();
+        let mut workspace_root = None;
+        if workspace.is_some() {
+            workspace_root.clone_from(&manifest_dir);
+        } else if let Some(manifest_dir) = &manifest_dir {
+            if let Some((root, root_workspace, workspace_dependencies)) =
+                find_workspace(&manifest, manifest_dir, &mut tables.probed_paths)?
+            {
+                workspace_root = Some(root);
+                workspace = Some(root_workspace);
+                tables.workspace_dependencies = workspace_dependencies;
+            }
+        }
+        tables.inherit();
+        resolve_path_dependencies(&mut tables, manifest_dir.as_deref(), workspace_root.as_deref());
+        Ok(Self {
+            manifest: Arc::new(manifest),
+            workspace: workspace.map(Arc::new),
+            tables: Arc::new(tables),
+            manifest_path: Some(manifest_path),
+            manifest_dir,
+            workspace_root,
+            dependencies: Dependencies::default(),
+            platform: None,
+            precedence: Precedence::default(),
+        })
     }
 
     /// Finds the crate with crate name, and returns its package information.
@@ -312,6 +836,9 @@ impl Manifest {
     /// The first argument of the closure is the original name of the package
     /// and the second argument is the version of the package.
     ///
+    /// If the `semver` feature is enabled, `Manifest::find_version` can be used
+    /// to match a version against the version requirement.
+    ///
     /// # Examples
     ///
     /// ```
@@ -336,15 +863,304 @@ impl Manifest {
     ///     quote!(extern crate #name as _foo;)
     /// }
     /// ```
-    pub fn find2<P>(&self, predicate: P) -> Option<Package>
+    pub fn find2<P>(&self, mut predicate: P) -> Option<Package>
     where
         P: FnMut(&str, &str) -> bool,
     {
-        find(&self.manifest, self.dependencies, predicate)
+        self.search(&mut predicate, false).ok().flatten()
+    }
+
+    /// Finds the crate with crate name, and returns its package information,
+    /// or [`Error::Ambiguous`] if the crate is found under multiple names.
+    ///
+    /// Unlike [`find`](Manifest::find), which returns the first match, this
+    /// checks all dependency tables to be searched, as if
+    /// [`Manifest::precedence`] were [`Precedence::Unique`]. This is useful for
+    /// procedural macros to report a clear error instead of generating code
+    /// that refers to the crate by a name that is valid only in some targets
+    /// (e.g., the crate renamed in `[dev-dependencies]`). The crate found in
+    /// multiple tables under the same name is not ambiguous, nor are different
+    /// crates accepted by the predicate (e.g., `foo` and `foo-core`), in which
+    /// case the first one found is returned.
+    ///
+    /// Returns [`Error::NotFound`] if the crate is not found, like
+    /// [`try_find`](Manifest::try_find).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    ///
+    /// use find_crate::{Error, Manifest};
+    ///
+    /// let manifest = Manifest::from_str(
+    ///     r#"
+    ///     [dependencies]
+    ///     foo = "0.1"
+    ///     [dev-dependencies]
+    ///     foo2 = { package = "foo", version = "0.1" }
+    ///     "#,
+    /// )
+    /// .unwrap();
+    /// match manifest.find_unique(|name| name == "foo") {
+    ///     Err(Error::Ambiguous(packages)) => {
+    ///         assert_eq!(packages[0].name, "foo");
+    ///         assert_eq!(packages[1].name, "foo2");
+    ///     }
+    ///     res => panic!("{res:?}"),
+    /// }
+    /// ```
+    pub fn find_unique<P>(&self, mut predicate: P) -> Result<Package>
+    where
+        P: FnMut(&str) -> bool,
+    {
+        let mut predicate = |s: &str, _: &str| predicate(s);
+        match self.search(&mut predicate, true)? {
+            Some(package) => Ok(package),
+            None => Err(Error::NotFound(self.not_found(&mut predicate))),
+        }
+    }
+
+    /// Finds the crate according to [`Manifest::precedence`].
+    ///
+    /// Returns [`Error::Ambiguous`] if the precedence is [`Precedence::Unique`]
+    /// or `unique` is `true`, and the crate is found under multiple names.
+    fn search<P>(&self, predicate: &mut P, unique: bool) -> Result<Option<Package>>
+    where
+        P: FnMut(&str, &str) -> bool,
+    {
+        let unique = unique || self.precedence == Precedence::Unique;
+        model::find(&self.entries(), unique, |entry| entry.package(self, predicate))
+            .map_err(Error::Ambiguous)
+    }
+
+    /// Finds the crate with crate name, and returns its package information,
+    /// or [`Error::NotFound`] describing why the crate was not found.
+    ///
+    /// This is the same as [`find`](Manifest::find), except that if the crate
+    /// is not found, the returned [`NotFound`] contains:
+    ///
+    /// - The sections that were searched, based on [`Manifest::dependencies`].
+    /// - The names of dependencies that are close to a name accepted by the
+    ///   predicate (e.g., `foo_core` when searching `foo-core`).
+    /// - The dependencies accepted by the predicate but filtered out by
+    ///   [`Manifest::dependencies`] or [`Manifest::platform`].
+    ///
+    /// Since the predicate is also called with names that are not in the
+    /// manifest to find similar names, it should not have side effects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    ///
+    /// use find_crate::{Error, Manifest};
+    ///
+    /// let manifest = Manifest::from_str(
+    ///     r#"
+    ///     [dependencies]
+    ///     foo_core = "0.1"
+    ///     "#,
+    /// )
+    /// .unwrap();
+    /// match manifest.try_find(|name| name == "foo-core") {
+    ///     Err(Error::NotFound(e)) => assert_eq!(e.similar_names(), ["foo_core"]),
+    ///     res => panic!("{res:?}"),
+    /// }
+    /// ```
+    pub fn try_find<P>(&self, mut predicate: P) -> Result<Package>
+    where
+        P: FnMut(&str) -> bool,
+    {
+        self.try_find2(|s, _| predicate(s))
+    }
+
+    /// Finds the crate with crate name and version, and returns its package
+    /// information, or [`Error::NotFound`] describing why the crate was not
+    /// found.
+    ///
+    /// This is the same as [`find2`](Manifest::find2), except that if the crate
+    /// is not found, this returns [`Error::NotFound`] like
+    /// [`try_find`](Manifest::try_find). In addition, the dependencies whose
+    /// version requirement is rejected by the predicate, but that are accepted
+    /// with any version requirement (`*`), are reported as filtered out.
+    pub fn try_find2<P>(&self, mut predicate: P) -> Result<Package>
+    where
+        P: FnMut(&str, &str) -> bool,
+    {
+        match self.search(&mut predicate, false)? {
+            Some(package) => Ok(package),
+            None => Err(Error::NotFound(self.not_found(&mut predicate))),
+        }
+    }
+
+    /// Collects the details of [`Error::NotFound`] for the predicate that did
+    /// not match any searched dependency.
+    fn not_found(&self, predicate: &mut dyn FnMut(&str, &str) -> bool) -> NotFound {
+        let all = Self { dependencies: Dependencies::All, platform: None, ..self.clone() };
+        let mut similar_names: Vec<String> = vec![];
+        let mut filtered = vec![];
+        for entry in all.entries() {
+            // None of the dependencies that were searched match, so all
+            // dependencies that match here were filtered out.
+            let version = entry.dependency.version.as_deref().unwrap_or("*");
+            if let Some(package) = entry.package(&all, &mut |name, version| {
+                predicate(name, version) || predicate(name, "*")
+            }) {
+                filtered.push(package);
+                continue;
+            }
+            for name in entry.dependency.package.as_deref().into_iter().chain([&**entry.key]) {
+                if !similar_names.iter().any(|n| n == name)
+                    && one_edit_variants(name)
+                        .iter()
+                        .any(|variant| predicate(variant, version) || predicate(variant, "*"))
+                {
+                    similar_names.push(name.to_owned());
+                }
+            }
+        }
+        NotFound { sections: self.dependencies.as_slice(), similar_names, filtered }
+    }
+
+    /// Finds the crate with the specified original name whose version
+    /// requirement matches the specified version, and returns its package
+    /// information.
+    ///
+    /// The version requirement is parsed as [`semver::VersionReq`], so all
+    /// syntax supported by cargo (caret, tilde, wildcard, and comparison
+    /// requirements) is supported. The dependency without version requirement
+    /// (e.g., path dependency without `version` key) matches any version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use find_crate::Manifest;
+    /// use proc_macro2::{Ident, Span, TokenStream};
+    /// use quote::quote;
+    /// use semver::Version;
+    ///
+    /// fn import() -> TokenStream {
+    ///     let version = Version::parse("0.3.0").unwrap();
+    ///     let manifest = Manifest::new().unwrap();
+    ///     let name = manifest.find_version("foo", &version).unwrap().name;
+    ///     let name = Ident::new(&name, Span::call_site());
+    ///     // If your proc-macro crate is 2018 edition, use `quote!(use #name as _foo;)` instead.
+    ///     quote!(extern crate #name as _foo;)
+    /// }
+    /// ```
+    #[cfg(feature = "semver")]
+    #[must_use]
+    pub fn find_version(&self, name: &str, version: &semver::Version) -> Option<Package> {
+        self.find2(|n, req| {
+            n == name && semver::VersionReq::parse(req).is_ok_and(|req| req.matches(version))
+        })
+    }
+
+    /// Finds all crates with crate name, and returns an iterator over their
+    /// package information.
+    ///
+    /// Unlike [`find`](Manifest::find), this returns all matches in the order
+    /// in which they are searched. [`Package::section`] and [`Package::target`]
+    /// tell where each package was found.
+    ///
+    /// The argument of the closure is the original name of the package.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use find_crate::Manifest;
+    ///
+    /// fn check_renamed() -> Result<(), String> {
+    ///     let manifest = Manifest::new().unwrap();
+    ///     let mut packages = manifest.find_all(|name| name == "foo");
+    ///     if let Some(first) = packages.next() {
+    ///         for package in packages {
+    ///             if package.name != first.name {
+    ///                 return Err(format!(
+    ///                     "`foo` is renamed to `{}` in [{}] and `{}` in [{}]",
+    ///                     first.name,
+    ///                     first.section(),
+    ///                     package.name,
+    ///                     package.section(),
+    ///                 ));
+    ///             }
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn find_all<P>(&self, predicate: P) -> FindAll<'_, P>
+    where
+        P: FnMut(&str) -> bool,
+    {
+        FindAll { manifest: self, entries: self.entries().into_iter(), predicate }
+    }
+
+    /// Checks the dependency tables of this manifest, and returns the first
+    /// problem found as [`Error::InvalidManifest`].
+    ///
+    /// Malformed values, such as `foo = 5` or non-string `version`, are always
+    /// reported when the manifest is created. In addition, this reports the
+    /// following problems that are otherwise ignored when finding crates (and
+    /// thus reported as [`Error::NotFound`] by [`find_crate`]).
+    ///
+    /// Problems that cargo rejects:
+    ///
+    /// - Invalid crate names, in the dependency key or in `package`.
+    /// - `workspace = false`.
+    /// - Dependencies inherited from the workspace that are not found in
+    ///   `[workspace.dependencies]`. This is checked only if the workspace root
+    ///   manifest is known.
+    /// - `optional = true` in `[workspace.dependencies]`.
+    ///
+    /// Problems that cargo only warns about as unused manifest keys:
+    ///
+    /// - Unknown keys in a dependency.
+    /// - Keys such as `version` and `path` specified with `workspace = true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    ///
+    /// use find_crate::Manifest;
+    ///
+    /// let manifest = Manifest::from_str(
+    ///     r#"
+    ///     [dependencies]
+    ///     foo = { version = "0.1", feature = ["std"] }
+    ///     "#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(
+    ///     manifest.validate().unwrap_err().to_string(),
+    ///     "The manifest is invalid because: unknown key `feature` in [dependencies.foo] section",
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        let source = Source { text: None, path: self.manifest_path.as_deref() };
+        let root_manifest_path = self.root_manifest_path();
+        let workspace_source = Source { text: None, path: root_manifest_path.as_deref() };
+        self.tables.validate(source, workspace_source)
+    }
+
+    fn entries(&self) -> Vec<model::Entry<'_, 'static>> {
+        self.tables.entries(self.dependencies, self.platform.as_ref(), self.precedence)
     }
 
     /// The package for the crate that this manifest represents.
     ///
+    /// If the version is inherited from the workspace (`version.workspace = true`),
+    /// this returns the version specified in `[workspace.package]` section of
+    /// the workspace root manifest.
+    ///
+    /// Like [`find_or_itself`](Manifest::find_or_itself), whether the returned
+    /// package refers to the current crate itself (`crate`) is determined by
+    /// [`target_kind`](Manifest::target_kind): when compiling doctests,
+    /// integration tests, examples, or binaries, `Package::to_path_tokens`
+    /// returns the path to the library as an external crate.
+    ///
     /// # Examples
     ///
     /// ```
@@ -360,22 +1176,54 @@ impl Manifest {
     /// }
     /// ```
     pub fn crate_package(&self) -> Result<Package> {
+        let source = Source { text: None, path: self.manifest_path.as_deref() };
+        let root_manifest_path = self.root_manifest_path();
+        let workspace_source = Source { text: None, path: root_manifest_path.as_deref() };
+
         let package_section = self
             .manifest
             .get("package")
-            .ok_or_else(|| Error::InvalidManifest("[package] section is missing".to_owned()))?;
+            .ok_or_else(|| source.invalid("[package] section is missing".to_owned(), &[]))?;
 
         let package_key_value = package_section.get("name").ok_or_else(|| {
-            Error::InvalidManifest("[package] section is missing `name`".to_owned())
+            source.invalid("[package] section is missing `name`".to_owned(), &["package"])
         })?;
 
         let package_key = package_key_value.as_str().ok_or_else(|| {
-            Error::InvalidManifest("`name` in [package] section is not a string".to_owned())
+            source.invalid("`name` in [package] section is not a string".to_owned(), &[
+                "package", "name",
+            ])
         })?;
 
         let package_version = match package_section.get("version") {
+            // Inherited from the workspace: https://doc.rust-lang.org/cargo/reference/workspaces.html#the-package-table
+            Some(Value::Table(package_version_table))
+                if package_version_table.get("workspace").and_then(Value::as_bool)
+                    == Some(true) =>
+            {
+                let workspace_package_version_value = self
+                    .workspace
+                    .as_deref()
+                    .and_then(|workspace| workspace.get("package")?.get("version"))
+                    .ok_or_else(|| {
+                        source.invalid(
+                            "`version` in [package] section is inherited from the workspace, \
+                             but [workspace.package] section is missing `version`"
+                                .to_owned(),
+                            &["package", "version"],
+                        )
+                    })?;
+                workspace_package_version_value.as_str().ok_or_else(|| {
+                    workspace_source.invalid(
+                        "`version` in [workspace.package] section is not a string".to_owned(),
+                        &["workspace", "package", "version"],
+                    )
+                })?
+            }
             Some(package_version_value) => package_version_value.as_str().ok_or_else(|| {
-                Error::InvalidManifest("`version` in [package] section is not a string".to_owned())
+                source.invalid("`version` in [package] section is not a string".to_owned(), &[
+                    "package", "version",
+                ])
             })?,
             // Cargo supports version-less manifests: https://github.com/rust-lang/cargo/pull/12786
             None => "0.0.0",
@@ -384,95 +1232,553 @@ impl Manifest {
         let package = Package {
             key: package_key.to_owned(),
             package: None,
-            name: package_key.replace('-', "_"),
+            name: lib_name(&self.manifest).unwrap_or_else(|| package_key.replace('-', "_")),
             version: package_version.to_owned(),
+            section: "package",
+            target: None,
+            features: vec![],
+            default_features: true,
+            optional: false,
+            source: None,
+            itself: self.target_kind().is_lib(),
         };
 
         Ok(package)
     }
+
+    /// Finds the crate with crate name, including the crate that this manifest
+    /// represents, and returns whether it is the current crate itself or an
+    /// external crate.
+    ///
+    /// The argument of the closure is the original name of the package.
+    ///
+    /// Since a crate never lists itself as a dependency, [`find`](Manifest::find)
+    /// cannot find the current crate. This function checks the package returned
+    /// by [`crate_package`](Manifest::crate_package) first, and returns
+    /// [`FoundCrate::Itself`] if the library of the current crate is being
+    /// compiled. Otherwise (e.g., when compiling doctests, integration tests,
+    /// examples, or binaries, where the library is used as an external crate),
+    /// this returns [`FoundCrate::Named`].
+    ///
+    /// Whether the library is being compiled is determined by
+    /// [`target_kind`](Manifest::target_kind). If it is unknown, the library
+    /// is assumed to be compiled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use find_crate::{FoundCrate, Manifest};
+    /// use proc_macro2::{Ident, Span, TokenStream};
+    /// use quote::quote;
+    ///
+    /// fn foo_path() -> TokenStream {
+    ///     let manifest = Manifest::new().unwrap();
+    ///     match manifest.find_or_itself(|name| name == "foo").unwrap() {
+    ///         FoundCrate::Itself => quote!(crate),
+    ///         FoundCrate::Named(package) => {
+    ///             let name = Ident::new(&package.name, Span::call_site());
+    ///             quote!(::#name)
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn find_or_itself<P>(&self, mut predicate: P) -> Option<FoundCrate>
+    where
+        P: FnMut(&str) -> bool,
+    {
+        if let Ok(package) = self.crate_package() {
+            if predicate(package.original_name()) {
+                if package.itself {
+                    return Some(FoundCrate::Itself);
+                }
+                return Some(FoundCrate::Named(package));
+            }
+        }
+        self.find(predicate).map(FoundCrate::Named)
+    }
+
+    /// Returns the kind of the target of this package that is being compiled.
+    ///
+    /// This is determined by the environment variables that cargo sets when
+    /// compiling crates ([`CARGO_CRATE_NAME`], [`CARGO_BIN_NAME`], and
+    /// [`CARGO_TARGET_TMPDIR`]), the target tables (`[lib]`, `[[bench]]`, etc.),
+    /// and the targets discovered automatically from the package directory
+    /// (`benches/*.rs`, etc.).
+    ///
+    /// Note that this function needs to be used in the context of proc-macro,
+    /// because these environment variables are set only at compile time.
+    /// Returns [`TargetKind::Unknown`] if they are not set.
+    ///
+    /// [`CARGO_CRATE_NAME`]: https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-crates
+    /// [`CARGO_BIN_NAME`]: https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-crates
+    /// [`CARGO_TARGET_TMPDIR`]: https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-crates
+    #[must_use]
+    pub fn target_kind(&self) -> TargetKind {
+        self.target_kind_with(|key| env::var(key).ok())
+    }
+
+    /// Returns the kind of the target of this package that is being compiled,
+    /// using the specified function to get environment variables.
+    ///
+    /// This is useful for using the tracked environment variables API of
+    /// proc-macro ([`proc_macro::tracked_env::var`]) on nightly.
+    ///
+    /// See [`target_kind`](Manifest::target_kind) for more.
+    ///
+    /// [`proc_macro::tracked_env::var`]: https://doc.rust-lang.org/nightly/proc_macro/tracked_env/fn.var.html
+    pub fn target_kind_with<F>(&self, env: F) -> TargetKind
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let lib_name = lib_name(&self.manifest);
+        target::target_kind(&self.manifest, self.manifest_dir.as_deref(), lib_name.as_deref(), env)
+    }
+
+    /// Reads `Cargo.lock` of the workspace (or of the package if it is not a
+    /// member of a workspace) that this manifest belongs to.
+    ///
+    /// The returned lockfile can be used to get the exact resolved version of
+    /// the packages returned by [`find`](Manifest::find).
+    ///
+    /// Returns [`Error::NotFoundLockfile`] if the lockfile does not exist or this
+    /// manifest was not read from a file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use find_crate::Manifest;
+    ///
+    /// fn foo_version() -> Option<String> {
+    ///     let manifest = Manifest::new().ok()?;
+    ///     let package = manifest.find(|name| name == "foo")?;
+    ///     let lockfile = manifest.lockfile().ok()?;
+    ///     Some(lockfile.resolve(&package)?.version().to_owned())
+    /// }
+    /// ```
+    pub fn lockfile(&self) -> Result<Lockfile> {
+        let path = self.lockfile_path().ok_or(Error::NotFoundLockfile)?;
+        let mut lockfile = Lockfile::from_path(&path)?;
+        if let Ok(package) = self.crate_package() {
+            lockfile.root = Some((package.key, package.version));
+        }
+        Ok(lockfile)
+    }
+
+    /// Returns the path to `Cargo.lock` read by [`lockfile`](Manifest::lockfile)
+    /// if it exists.
+    fn lockfile_path(&self) -> Option<PathBuf> {
+        self.workspace_root
+            .as_ref()
+            .or(self.manifest_dir.as_ref())
+            .map(|dir| dir.join("Cargo.lock"))
+            .filter(|path| path.is_file())
+    }
+
+    /// Returns the path to the workspace root manifest.
+    fn root_manifest_path(&self) -> Option<PathBuf> {
+        self.workspace_root.as_ref().map(|root| root.join("Cargo.toml"))
+    }
+
+    /// Returns the paths to the manifest files read to create this manifest:
+    /// this manifest, the workspace root manifest, and the manifests of the
+    /// path dependencies.
+    fn manifest_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<_> = self.manifest_path.iter().cloned().collect();
+        if let Some(workspace_root) = &self.workspace_root {
+            if self.manifest_dir.as_ref() != Some(workspace_root) {
+                paths.push(workspace_root.join("Cargo.toml"));
+            }
+        }
+        for path in &self.tables.path_manifests {
+            if !paths.contains(path) {
+                paths.push(path.clone());
+            }
+        }
+        paths
+    }
+
+    /// Returns the paths to the files that the information provided by this
+    /// manifest depends on: this manifest, the workspace root manifest if this
+    /// package is a member of a workspace, the manifests of the path
+    /// dependencies, and `Cargo.lock` if it exists.
+    ///
+    /// Procedural macros whose output depends on this manifest should make
+    /// the compiler track these files, so that the macros are re-expanded when
+    /// they are modified (e.g., when a dependency is renamed). On nightly, this
+    /// can be done by passing each path to [`proc_macro::tracked_path::path`].
+    /// On stable, this can be done by emitting the tokens returned by
+    /// `Manifest::to_tracking_tokens` (requires the `proc-macro2` feature).
+    ///
+    /// If this manifest was not read from a file, only the manifests of the
+    /// path dependencies are returned.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// #![feature(proc_macro_tracked_path)]
+    /// extern crate proc_macro;
+    ///
+    /// use find_crate::Manifest;
+    ///
+    /// fn track(manifest: &Manifest) {
+    ///     for path in manifest.tracked_paths() {
+    ///         if let Some(path) = path.to_str() {
+    ///             proc_macro::tracked_path::path(path);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`proc_macro::tracked_path::path`]: https://doc.rust-lang.org/nightly/proc_macro/tracked_path/fn.path.html
+    #[must_use]
+    pub fn tracked_paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.manifest_paths();
+        paths.extend(self.lockfile_path());
+        paths
+    }
+
+    /// Returns tokens that make the compiler track the files returned by
+    /// [`tracked_paths`](Manifest::tracked_paths), so that the macro is
+    /// re-expanded when they are modified.
+    ///
+    /// The tokens are items like `const _: &[u8] = include_bytes!("/path/to/Cargo.toml");`
+    /// and must be emitted in a position where items are allowed. Paths that are
+    /// not valid UTF-8 are skipped. The tokens work in all editions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use find_crate::Manifest;
+    /// use proc_macro2::TokenStream;
+    /// use quote::quote;
+    ///
+    /// fn expand(input: TokenStream) -> TokenStream {
+    ///     let manifest = Manifest::new().unwrap();
+    ///     let path = manifest.find(|name| name == "foo").unwrap().to_path_tokens();
+    ///     let tracking = manifest.to_tracking_tokens();
+    ///     quote! {
+    ///         #tracking
+    ///         use #path as _foo;
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "proc-macro2")]
+    #[must_use]
+    pub fn to_tracking_tokens(&self) -> proc_macro2::TokenStream {
+        use proc_macro2::{
+            Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree,
+        };
+
+        let span = Span::call_site();
+        let mut tokens = TokenStream::new();
+        for path in self.tracked_paths() {
+            let Some(path) = path.to_str() else { continue };
+            tokens.extend([
+                TokenTree::Ident(Ident::new("const", span)),
+                TokenTree::Ident(Ident::new("_", span)),
+                TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+                TokenTree::Punct(Punct::new('&', Spacing::Alone)),
+                TokenTree::Group(Group::new(
+                    Delimiter::Bracket,
+                    TokenTree::Ident(Ident::new("u8", span)).into(),
+                )),
+                TokenTree::Punct(Punct::new('=', Spacing::Alone)),
+                // Not `::core::include_bytes!`, because `core` is not in the
+                // extern prelude in the 2015 edition.
+                TokenTree::Ident(Ident::new("include_bytes", span)),
+                TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+                TokenTree::Group(Group::new(
+                    Delimiter::Parenthesis,
+                    TokenTree::Literal(Literal::string(path)).into(),
+                )),
+                TokenTree::Punct(Punct::new(';', Spacing::Alone)),
+            ]);
+        }
+        tokens
+    }
 }
 
 impl FromStr for Manifest {
     type Err = Error;
 
     /// Creates a new `Manifest` from a string containing a TOML file.
+    ///
+    /// If the manifest is the workspace root manifest, the dependencies
+    /// inherited from the workspace are resolved using its `[workspace]` section.
     fn from_str(manifest: &str) -> Result<Self, Self::Err> {
+        let (manifest, mut tables) = parse_manifest(manifest, false)?;
+        tables.inherit();
+        resolve_path_dependencies(&mut tables, None, None);
+        let workspace = manifest.get("workspace").and_then(Value::as_table).cloned().map(Arc::new);
         Ok(Self {
-            manifest: toml::from_str(manifest).map_err(|e| Error::Toml(TomlError { error: e }))?,
+            manifest: Arc::new(manifest),
+            workspace,
+            tables: Arc::new(tables),
+            manifest_path: None,
+            manifest_dir: None,
+            workspace_root: None,
             dependencies: Dependencies::default(),
+            platform: None,
+            precedence: Precedence::default(),
         })
     }
 }
 
+fn parse(manifest: &str) -> Result<Table> {
+    toml::from_str(manifest).map_err(|e| Error::Toml(TomlError::new(e, manifest)))
+}
+
+/// Parses the manifest, and deserializes its dependency tables, or only
+/// `[workspace.dependencies]` if `workspace_only` is `true`.
+fn parse_manifest(
+    manifest: &str,
+    workspace_only: bool,
+) -> Result<(Table, model::DependencyTables<'static>)> {
+    let toml_error = |e| Error::Toml(TomlError::new(e, manifest));
+    let root = DeTable::parse(manifest).map_err(toml_error)?;
+    let tables = model::dependency_tables(root.clone(), manifest, workspace_only)?.into_owned();
+    Ok((Table::deserialize(Deserializer::from(root)).map_err(toml_error)?, tables))
+}
+
+/// An iterator over all crates that match the predicate.
+///
+/// This struct is created by [`Manifest::find_all`]. See its documentation for more.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct FindAll<'a, P> {
+    manifest: &'a Manifest,
+    entries: vec::IntoIter<model::Entry<'a, 'static>>,
+    predicate: P,
+}
+
+impl<P> Iterator for FindAll<'_, P>
+where
+    P: FnMut(&str) -> bool,
+{
+    type Item = Package;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let predicate = &mut self.predicate;
+        self.entries.find_map(|entry| entry.package(self.manifest, &mut |s, _| predicate(s)))
+    }
+}
+
+impl<P> fmt::Debug for FindAll<'_, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FindAll").finish_non_exhaustive()
+    }
+}
+
+/// Returns the common misspellings of the specified crate name: the name with
+/// all `-` and `_` swapped, the lowercase name, and the names with one
+/// character deleted, doubled, or swapped with the next one, or with `-` or `_`
+/// inserted or replaced.
+///
+/// Each variant is passed to the user-provided predicate, so the number of
+/// variants is kept linear in the length of the name, instead of trying every
+/// character at every position.
+fn one_edit_variants(name: &str) -> Vec<String> {
+    const SEPARATORS: [char; 2] = ['-', '_'];
+    let chars: Vec<char> = name.chars().collect();
+    let mut variants = vec![name.replace('-', "_"), name.replace('_', "-"), name.to_lowercase()];
+    for i in 0..=chars.len() {
+        for c in SEPARATORS {
+            let mut variant = chars.clone();
+            variant.insert(i, c);
+            variants.push(variant.into_iter().collect());
+        }
+        if i == chars.len() {
+            break;
+        }
+        let mut variant = chars.clone();
+        variant.remove(i);
+        variants.push(variant.into_iter().collect());
+        let mut variant = chars.clone();
+        variant.insert(i, chars[i]);
+        variants.push(variant.into_iter().collect());
+        for c in SEPARATORS.into_iter().filter(|&c| c != chars[i]) {
+            let mut variant = chars.clone();
+            variant[i] = c;
+            variants.push(variant.into_iter().collect());
+        }
+        if i + 1 < chars.len() {
+            let mut variant = chars.clone();
+            variant.swap(i, i + 1);
+            variants.push(variant.into_iter().collect());
+        }
+    }
+    variants.retain(|variant| variant != name);
+    variants.sort_unstable();
+    variants.dedup();
+    variants
+}
+
+/// Returns the crate name of the library target of the package that the
+/// specified manifest represents.
+///
+/// This is the value of `name` in `[lib]` section if it is specified, otherwise
+/// the package name with `-` replaced with `_`.
+fn lib_name(manifest: &Table) -> Option<String> {
+    let package_name = manifest.get("package")?.get("name")?.as_str()?;
+    let lib_name = manifest.get("lib").and_then(|lib| lib.get("name")?.as_str());
+    Some(lib_name.unwrap_or(package_name).replace('-', "_"))
+}
+
 fn manifest_path() -> Result<PathBuf> {
     let mut path: PathBuf = env::var_os(MANIFEST_DIR).ok_or(Error::NotFoundManifestDir)?.into();
     path.push("Cargo.toml");
     Ok(path)
 }
 
-fn find<P>(manifest: &Table, dependencies: Dependencies, mut predicate: P) -> Option<Package>
-where
-    P: FnMut(&str, &str) -> bool,
-{
-    fn find_inner<P>(table: &Table, dependencies: &str, predicate: &mut P) -> Option<Package>
-    where
-        P: FnMut(&str, &str) -> bool,
-    {
-        find_from_dependencies(table.get(dependencies)?.as_table()?, predicate)
+/// Reads the manifests of the path dependencies that are not renamed to get the
+/// names of their library targets, which are their crate names.
+///
+/// The paths are resolved as described in [`DependencySource::Path`]. Relative
+/// paths are skipped if the base directory is unknown.
+fn resolve_path_dependencies(
+    tables: &mut model::DependencyTables<'_>,
+    manifest_dir: Option<&Path>,
+    workspace_root: Option<&Path>,
+) {
+    let mut lib_names: Vec<(PathBuf, Option<String>)> = vec![];
+    let mut probed_paths: Vec<PathBuf> = vec![];
+    for dependency in tables.dependencies_mut() {
+        if dependency.package.is_some() {
+            continue;
+        }
+        let Some(path) = dependency.resolved_path(manifest_dir, workspace_root) else { continue };
+        if !path.is_absolute() {
+            continue;
+        }
+        let manifest_path = path.join("Cargo.toml");
+        if let Some((_, lib_name)) = lib_names.iter().find(|(p, _)| *p == manifest_path) {
+            dependency.lib_name.clone_from(lib_name);
+            continue;
+        }
+        if !probed_paths.contains(&manifest_path) {
+            probed_paths.push(manifest_path.clone());
+        }
+        let Ok(manifest) = fs::read_to_string(&manifest_path) else { continue };
+        dependency.lib_name = toml::from_str(&manifest).ok().and_then(|m| lib_name(&m));
+        lib_names.push((manifest_path, dependency.lib_name.clone()));
     }
-    fn find_target<P>(table: &Table, dependencies: &str, predicate: &mut P) -> Option<Package>
-    where
-        P: FnMut(&str, &str) -> bool,
-    {
-        table.values().find_map(|table| {
-            let table = table.as_table()?;
-            find_inner(table, dependencies, predicate)
-                .or_else(|| find_target(table, dependencies, predicate))
-        })
+    tables.path_manifests = lib_names.into_iter().map(|(path, _)| path).collect();
+    tables.probed_paths.extend(probed_paths);
+}
+
+/// Reads and parses the specified workspace root manifest file, and
+/// deserializes its `[workspace.dependencies]`.
+fn read_root_manifest(path: &Path) -> Result<(Table, model::DependencyTables<'static>)> {
+    parse_manifest(&fs::read_to_string(path)?, true).map_err(|e| e.with_path(path))
+}
+
+/// Finds the workspace root directory, the `[workspace]` section, and
+/// `[workspace.dependencies]` of the workspace root manifest of the package that
+/// the specified manifest represents.
+///
+/// The paths to the manifest files looked for are added to `probed_paths`.
+fn find_workspace(
+    manifest: &Table,
+    manifest_dir: &Path,
+    probed_paths: &mut Vec<PathBuf>,
+) -> Result<Option<(PathBuf, Table, Option<model::WorkspaceDependencies<'static>>)>> {
+    // The workspace root is specified explicitly.
+    // https://doc.rust-lang.org/cargo/reference/manifest.html#the-workspace-field
+    if let Some(root) = manifest.get("package").and_then(|p| p.get("workspace")?.as_str()) {
+        let root = manifest_dir.join(root);
+        probed_paths.push(root.join("Cargo.toml"));
+        let (mut root_manifest, tables) = read_root_manifest(&root.join("Cargo.toml"))?;
+        return Ok(match root_manifest.remove("workspace") {
+            Some(Value::Table(workspace)) => Some((root, workspace, tables.workspace_dependencies)),
+            _ => None,
+        });
     }
 
-    dependencies
-        .as_slice()
-        .iter()
-        .find_map(|dependencies| find_inner(manifest, dependencies, &mut predicate))
-        .or_else(|| {
-            dependencies.as_slice().iter().find_map(|dependencies| {
-                find_target(manifest.get("target")?.as_table()?, dependencies, &mut predicate)
-            })
-        })
+    // Otherwise, the workspace root is the first parent directory that contains
+    // a manifest with `[workspace]` section that doesn't exclude this package.
+    // Like cargo, the search doesn't go beyond `target/package` (where `cargo
+    // package` verifies the package) and `CARGO_HOME` (where the packages from
+    // registries and git are extracted).
+    let cargo_home = cargo_home();
+    for (root, child) in manifest_dir.ancestors().skip(1).zip(manifest_dir.ancestors()) {
+        if root.ends_with("target/package") || cargo_home.as_deref() == Some(child) {
+            break;
+        }
+        let root_manifest_path = root.join("Cargo.toml");
+        probed_paths.push(root_manifest_path.clone());
+        if !root_manifest_path.is_file() {
+            continue;
+        }
+        let (mut root_manifest, tables) = read_root_manifest(&root_manifest_path)?;
+        let Some(Value::Table(workspace)) = root_manifest.remove("workspace") else { continue };
+        let excluded = workspace.get("exclude").and_then(Value::as_array).is_some_and(|exclude| {
+            exclude.iter().filter_map(Value::as_str).any(|e| manifest_dir.starts_with(root.join(e)))
+        });
+        if !excluded {
+            return Ok(Some((root.to_path_buf(), workspace, tables.workspace_dependencies)));
+        }
+    }
+    Ok(None)
 }
 
-fn find_from_dependencies<P>(table: &Table, mut predicate: P) -> Option<Package>
-where
-    P: FnMut(&str, &str) -> bool,
-{
-    fn package<P>(value: &Value, version: &str, predicate: P) -> Option<String>
+/// Returns the `CARGO_HOME` directory.
+fn cargo_home() -> Option<PathBuf> {
+    // https://doc.rust-lang.org/cargo/guide/cargo-home.html
+    match env::var_os("CARGO_HOME") {
+        Some(home) => std::path::absolute(home).ok(),
+        #[allow(deprecated)] // home_dir is no longer deprecated since Rust 1.86.
+        None => env::home_dir().map(|home| home.join(".cargo")),
+    }
+}
+
+impl model::Entry<'_, '_> {
+    fn package<P>(&self, manifest: &Manifest, predicate: &mut P) -> Option<Package>
     where
-        P: FnOnce(&str, &str) -> bool,
+        P: FnMut(&str, &str) -> bool,
     {
-        value
-            .as_table()?
-            .get("package")?
-            .as_str()
-            .and_then(|name| if predicate(name, version) { Some(name.to_owned()) } else { None })
-    }
-
-    fn version(value: &Value) -> Option<&str> {
-        value.as_str().or_else(|| value.as_table()?.get("version")?.as_str())
-    }
-
-    table.iter().find_map(|(key, value)| {
-        let version = version(value).unwrap_or("*");
-        let package = package(value, version, &mut predicate);
-        if package.is_some() || predicate(key, version) {
-            Some(Package {
-                key: key.clone(),
-                name: key.replace('-', "_"),
-                version: version.to_owned(),
-                package,
-            })
-        } else {
-            None
+        // https://doc.rust-lang.org/cargo/reference/specifying-dependencies.html
+        fn source(dependency: &model::Dependency<'_>, manifest: &Manifest) -> DependencySource {
+            let owned = |s: &Option<Cow<'_, str>>| s.as_deref().map(str::to_owned);
+            if let Some(url) = &dependency.git {
+                let reference = owned(&dependency.branch)
+                    .map(GitReference::Branch)
+                    .or_else(|| owned(&dependency.tag).map(GitReference::Tag))
+                    .or_else(|| owned(&dependency.rev).map(GitReference::Rev));
+                DependencySource::Git { url: (**url).to_owned(), reference }
+            } else if let Some(path) = dependency
+                .resolved_path(manifest.manifest_dir.as_deref(), manifest.workspace_root.as_deref())
+            {
+                DependencySource::Path(path)
+            } else if let Some(index) = owned(&dependency.registry_index) {
+                DependencySource::RegistryIndex(index)
+            } else {
+                DependencySource::Registry(owned(&dependency.registry))
+            }
         }
-    })
+
+        let model::Accepted { package, version } = self.accepts(predicate)?;
+        let dependency = self.dependency;
+        Some(Package {
+            key: (**self.key).to_owned(),
+            name: dependency.lib_name.clone().unwrap_or_else(|| self.key.replace('-', "_")),
+            version: version.to_owned(),
+            package: package.map(|package| (**package).to_owned()),
+            section: self.section,
+            target: self.target.map(|target| (**target).to_owned()),
+            features: dependency.features.iter().map(|feature| (**feature).to_owned()).collect(),
+            default_features: dependency.default_features.unwrap_or(true),
+            optional: dependency.optional.unwrap_or(false),
+            source: Some(source(dependency, manifest)),
+            itself: false,
+        })
+    }
+}
+
+impl model::Found for Package {
+    fn original_name(&self) -> &str {
+        self.original_name()
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
 }
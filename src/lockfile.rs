@@ -0,0 +1,188 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use alloc::{borrow::ToOwned as _, format, string::String, vec::Vec};
+use core::str::FromStr;
+use std::{fs, path::Path};
+
+use toml::value::{Table, Value};
+
+use crate::{Error, Package, Result};
+
+/// The lockfile of cargo (`Cargo.lock`).
+///
+/// See [`Manifest::lockfile`](crate::Manifest::lockfile) for how to read the
+/// lockfile of the current package.
+#[derive(Debug, Clone)]
+pub struct Lockfile {
+    packages: Vec<LockedPackage>,
+
+    // The name and version of the package that depends on the packages passed
+    // to `resolve`.
+    pub(crate) root: Option<(String, String)>,
+}
+
+/// The package information in the lockfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+    checksum: Option<String>,
+    dependencies: Vec<String>,
+}
+
+impl Lockfile {
+    /// Creates a new `Lockfile` from the specified lockfile.
+    pub fn from_path(lockfile_path: &Path) -> Result<Self> {
+        Self::from_str(&fs::read_to_string(lockfile_path)?).map_err(|e| e.with_path(lockfile_path))
+    }
+
+    /// Returns all packages in the lockfile.
+    #[must_use]
+    pub fn packages(&self) -> &[LockedPackage] {
+        &self.packages
+    }
+
+    /// Returns the package that the specified dependency resolved to.
+    ///
+    /// If the lockfile contains multiple versions of the package, the
+    /// dependencies of the current package are used to determine which one is
+    /// used. In this case, the lockfile must be read by [`Manifest::lockfile`].
+    ///
+    /// Returns `None` if the package is not found in the lockfile, or could not
+    /// be determined uniquely.
+    ///
+    /// [`Manifest::lockfile`]: crate::Manifest::lockfile
+    #[must_use]
+    pub fn resolve(&self, package: &Package) -> Option<&LockedPackage> {
+        let name = package.original_name();
+        let mut candidates = self.packages.iter().filter(|p| p.name == name);
+        let first = candidates.next()?;
+        if candidates.next().is_none() {
+            return Some(first);
+        }
+
+        let (root_name, root_version) = self.root.as_ref()?;
+        let root = self
+            .packages
+            .iter()
+            .find(|p| p.name == *root_name && p.version == *root_version && p.source.is_none())?;
+        let mut candidates = self
+            .packages
+            .iter()
+            .filter(|p| p.name == name && root.dependencies.iter().any(|d| p.is_referenced_by(d)));
+        let first = candidates.next()?;
+        if candidates.next().is_none() { Some(first) } else { None }
+    }
+}
+
+impl FromStr for Lockfile {
+    type Err = Error;
+
+    /// Creates a new `Lockfile` from a string containing a lockfile.
+    fn from_str(lockfile: &str) -> Result<Self, Self::Err> {
+        let lockfile = crate::parse(lockfile)?;
+        let packages = lockfile.get("package").and_then(Value::as_array).map_or(&[][..], |p| p);
+        // Lockfile version 1 stores checksums in [metadata] section.
+        let metadata = lockfile.get("metadata").and_then(Value::as_table);
+        let packages = packages
+            .iter()
+            .map(|package| {
+                LockedPackage::from_table(
+                    package.as_table().ok_or_else(|| {
+                        Error::InvalidLockfile("`package` is not an array of tables".to_owned())
+                    })?,
+                    metadata,
+                )
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { packages, root: None })
+    }
+}
+
+impl LockedPackage {
+    fn from_table(table: &Table, metadata: Option<&Table>) -> Result<Self> {
+        fn get<'a>(table: &'a Table, key: &str) -> Result<Option<&'a str>> {
+            match table.get(key) {
+                Some(value) => value.as_str().map(Some).ok_or_else(|| {
+                    Error::InvalidLockfile(format!(
+                        "`{key}` in [[package]] section is not a string"
+                    ))
+                }),
+                None => Ok(None),
+            }
+        }
+
+        let name = get(table, "name")?.ok_or_else(|| {
+            Error::InvalidLockfile("[[package]] section is missing `name`".to_owned())
+        })?;
+        let version = get(table, "version")?.ok_or_else(|| {
+            Error::InvalidLockfile("[[package]] section is missing `version`".to_owned())
+        })?;
+        let source = get(table, "source")?;
+        let checksum = match get(table, "checksum")? {
+            Some(checksum) => Some(checksum),
+            None => metadata.and_then(|metadata| {
+                let key = format!("checksum {name} {version} ({})", source?);
+                metadata.get(&key)?.as_str()
+            }),
+        };
+        let dependencies = table
+            .get("dependencies")
+            .and_then(Value::as_array)
+            .map(|d| d.iter().filter_map(Value::as_str).map(str::to_owned).collect())
+            .unwrap_or_default();
+        Ok(Self {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            source: source.map(str::to_owned),
+            checksum: checksum.map(str::to_owned),
+            dependencies,
+        })
+    }
+
+    /// Returns the name of the package.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the exact version of the package.
+    #[must_use]
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Returns the source of the package.
+    ///
+    /// This is a string such as `registry+https://github.com/rust-lang/crates.io-index`
+    /// for the registry dependencies, and `git+https://github.com/user/repo#<commit>`
+    /// for the git dependencies. Returns `None` for the path dependencies and
+    /// the workspace members.
+    #[must_use]
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Returns the checksum of the package.
+    ///
+    /// Returns `None` if the checksum is not recorded, e.g., for the path and
+    /// git dependencies.
+    #[must_use]
+    pub fn checksum(&self) -> Option<&str> {
+        self.checksum.as_deref()
+    }
+
+    /// Returns `true` if the specified entry in the `dependencies` of a locked
+    /// package refers to this package.
+    ///
+    /// The entry is `name`, `name version`, or `name version (source)`.
+    fn is_referenced_by(&self, dependency: &str) -> bool {
+        let mut iter = dependency.splitn(3, ' ');
+        iter.next() == Some(&self.name)
+            && iter.next().is_none_or(|version| version == self.version)
+            && iter.next().is_none_or(|source| {
+                source.strip_prefix('(').and_then(|s| s.strip_suffix(')')) == self.source.as_deref()
+            })
+    }
+}
@@ -0,0 +1,1070 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Typed model of the dependency tables of the manifest.
+//
+// The model is deserialized once when the manifest is created, and is searched
+// by `Manifest` and `ManifestRef` without parsing the manifest again.
+//
+// The strings in the model are borrowed from the source text where possible
+// (`ManifestRef`), and `Manifest` converts the model into an owned one with
+// `DependencyTables::into_owned`.
+
+use alloc::{
+    borrow::{Cow, ToOwned as _},
+    format,
+    string::String,
+    vec,
+    vec::Vec,
+};
+use core::{fmt, marker::PhantomData};
+use std::path::{Path, PathBuf};
+
+use serde::de::{
+    self, Deserialize, DeserializeSeed, Error as _, IgnoredAny, MapAccess, SeqAccess, Visitor,
+    value::{BorrowedStrDeserializer, StringDeserializer},
+};
+use toml::{
+    Spanned,
+    de::{DeTable, Deserializer},
+};
+
+use crate::{Dependencies, Platform, Precedence, Result, error::Source};
+
+/// The sections of the dependency tables, including the legacy spellings that
+/// cargo still accepts (`dev_dependencies` and `build_dependencies`).
+const SECTIONS: &[&str] = &[
+    "dependencies",
+    "dev-dependencies",
+    "dev_dependencies",
+    "build-dependencies",
+    "build_dependencies",
+];
+
+/// Returns all spellings of the specified section, in the order in which they
+/// are searched.
+///
+/// The table with the legacy spelling is searched only if the table with the
+/// hyphenated spelling for the same target is not specified (see
+/// [`DependencyTables::is_ignored`]).
+fn spellings(section: &str) -> &'static [&'static str] {
+    match section {
+        "dev-dependencies" => &["dev-dependencies", "dev_dependencies"],
+        "build-dependencies" => &["build-dependencies", "build_dependencies"],
+        _ => &["dependencies"],
+    }
+}
+
+/// The keys that cargo accepts in a dependency table.
+///
+/// https://doc.rust-lang.org/cargo/reference/specifying-dependencies.html
+const KEYS: &[&str] = &[
+    "version",
+    "package",
+    "features",
+    "default-features",
+    "default_features",
+    "optional",
+    "public",
+    "workspace",
+    "git",
+    "branch",
+    "tag",
+    "rev",
+    "path",
+    "base",
+    "registry",
+    "registry-index",
+    "artifact",
+    "lib",
+    "target",
+];
+
+/// The keys that cannot be specified with `workspace = true`, because they are
+/// specified in `[workspace.dependencies]`.
+const NOT_INHERITABLE_KEYS: &[&str] = &[
+    "version",
+    "package",
+    "git",
+    "branch",
+    "tag",
+    "rev",
+    "path",
+    "base",
+    "registry",
+    "registry-index",
+];
+
+/// A dependency: `foo = "0.1"` or `foo = { version = "0.1", ... }`.
+///
+/// https://doc.rust-lang.org/cargo/reference/specifying-dependencies.html
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Dependency<'a> {
+    pub(crate) version: Option<Cow<'a, str>>,
+    pub(crate) package: Option<Cow<'a, str>>,
+    pub(crate) features: Vec<Cow<'a, str>>,
+    pub(crate) default_features: Option<bool>,
+    pub(crate) optional: Option<bool>,
+    pub(crate) workspace: Option<bool>,
+    pub(crate) git: Option<Cow<'a, str>>,
+    pub(crate) branch: Option<Cow<'a, str>>,
+    pub(crate) tag: Option<Cow<'a, str>>,
+    pub(crate) rev: Option<Cow<'a, str>>,
+    pub(crate) path: Option<Cow<'a, str>>,
+    pub(crate) base: Option<Cow<'a, str>>,
+    pub(crate) registry: Option<Cow<'a, str>>,
+    pub(crate) registry_index: Option<Cow<'a, str>>,
+    // Whether the fields in `[workspace.dependencies]` have been merged into
+    // this dependency.
+    pub(crate) inherited: bool,
+    // The name of the library target of this path dependency, read from its
+    // manifest. This is `None` if this dependency is renamed.
+    pub(crate) lib_name: Option<String>,
+    // The keys specified in the table, used for validation.
+    keys: Vec<Cow<'a, str>>,
+}
+
+/// Converts the string borrowed from the source text into an owned one.
+fn owned(s: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(s.into_owned())
+}
+
+impl<'a> Dependency<'a> {
+    /// Merges the corresponding dependency in `[workspace.dependencies]` into
+    /// this dependency inherited from the workspace (`foo.workspace = true`).
+    ///
+    /// https://doc.rust-lang.org/cargo/reference/specifying-dependencies.html#inheriting-a-dependency-from-a-workspace
+    fn inherit(&self, workspace: &Self) -> Self {
+        let or =
+            |a: &Option<Cow<'a, str>>, b: &Option<Cow<'a, str>>| a.as_ref().or(b.as_ref()).cloned();
+        // Features are additive with the features from `[workspace.dependencies]`.
+        let mut features = workspace.features.clone();
+        features.extend_from_slice(&self.features);
+        Self {
+            version: or(&self.version, &workspace.version),
+            package: or(&self.package, &workspace.package),
+            features,
+            default_features: self.default_features.or(workspace.default_features),
+            // Workspace dependencies cannot be optional.
+            optional: self.optional,
+            git: or(&self.git, &workspace.git),
+            branch: or(&self.branch, &workspace.branch),
+            tag: or(&self.tag, &workspace.tag),
+            rev: or(&self.rev, &workspace.rev),
+            path: or(&self.path, &workspace.path),
+            base: or(&self.base, &workspace.base),
+            registry: or(&self.registry, &workspace.registry),
+            registry_index: or(&self.registry_index, &workspace.registry_index),
+            inherited: true,
+            lib_name: None,
+            // Keep the keys specified in this table for validation.
+            workspace: self.workspace,
+            keys: self.keys.clone(),
+        }
+    }
+
+    fn into_owned(self) -> Dependency<'static> {
+        Dependency {
+            version: self.version.map(owned),
+            package: self.package.map(owned),
+            features: self.features.into_iter().map(owned).collect(),
+            default_features: self.default_features,
+            optional: self.optional,
+            workspace: self.workspace,
+            git: self.git.map(owned),
+            branch: self.branch.map(owned),
+            tag: self.tag.map(owned),
+            rev: self.rev.map(owned),
+            path: self.path.map(owned),
+            base: self.base.map(owned),
+            registry: self.registry.map(owned),
+            registry_index: self.registry_index.map(owned),
+            inherited: self.inherited,
+            lib_name: self.lib_name,
+            keys: self.keys.into_iter().map(owned).collect(),
+        }
+    }
+
+    /// Returns the path of this path dependency, resolved as described in
+    /// [`DependencySource::Path`](crate::DependencySource::Path).
+    pub(crate) fn resolved_path(
+        &self,
+        manifest_dir: Option<&Path>,
+        workspace_root: Option<&Path>,
+    ) -> Option<PathBuf> {
+        let path = self.path.as_deref()?;
+        let base_dir = match self.base.as_deref() {
+            // The built-in path base.
+            Some("workspace") => workspace_root,
+            // Other path bases are defined in the cargo configuration.
+            Some(_) => None,
+            None if self.inherited => workspace_root,
+            None => manifest_dir,
+        };
+        Some(match base_dir {
+            Some(base_dir) => base_dir.join(path),
+            None => PathBuf::from(path),
+        })
+    }
+
+    /// Returns the problem of this dependency that cargo would reject or warn
+    /// about.
+    fn validate(
+        &self,
+        source: Source<'_>,
+        section: &str,
+        path: &[&str],
+        key: &str,
+        in_workspace: bool,
+    ) -> Result<()> {
+        let invalid = |reason: String, field: Option<&str>| {
+            Err(source.invalid(reason, &entry_path(path, key, field)))
+        };
+        if !is_crate_name(key) {
+            return invalid(
+                format!("`{key}` in [{section}] section is not a valid crate name"),
+                None,
+            );
+        }
+        let section = format!("{section}.{key}");
+        // `package` of the inherited dependency is checked in `[workspace.dependencies]`.
+        if let Some(package) =
+            self.package.as_deref().filter(|&package| !self.inherited && !is_crate_name(package))
+        {
+            return invalid(
+                format!("`package` in [{section}] section is not a valid crate name: `{package}`"),
+                Some("package"),
+            );
+        }
+        if let Some(unknown) = self.keys.iter().find(|&key| !KEYS.contains(&&**key)) {
+            return invalid(
+                format!("unknown key `{unknown}` in [{section}] section"),
+                Some(unknown),
+            );
+        }
+        match self.workspace {
+            Some(_) if in_workspace => {
+                return invalid(
+                    format!("`workspace` cannot be specified in [{section}] section"),
+                    Some("workspace"),
+                );
+            }
+            Some(false) => {
+                return invalid(
+                    format!("`workspace` in [{section}] section cannot be false"),
+                    Some("workspace"),
+                );
+            }
+            Some(true) => {
+                if let Some(field) =
+                    self.keys.iter().find(|&key| NOT_INHERITABLE_KEYS.contains(&&**key))
+                {
+                    return invalid(
+                        format!(
+                            "`{field}` cannot be specified with `workspace = true` in [{section}] section"
+                        ),
+                        Some(field),
+                    );
+                }
+            }
+            None => {}
+        }
+        if in_workspace && self.optional == Some(true) {
+            return invalid(
+                format!("`optional` cannot be true in [{section}] section"),
+                Some("optional"),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Returns the keys of the dependency `key` in the table at `path`, or of its
+/// `field` if specified.
+fn entry_path<'a>(path: &[&'a str], key: &'a str, field: Option<&'a str>) -> Vec<&'a str> {
+    path.iter().copied().chain([key]).chain(field).collect()
+}
+
+/// Returns `true` if the specified string is a valid crate name.
+///
+/// Like cargo, this accepts a Unicode XID start character or `_`, followed by
+/// Unicode XID continue characters or `-`.
+fn is_crate_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| unicode_ident::is_xid_start(c) || c == '_')
+        && chars.all(|c| unicode_ident::is_xid_continue(c) || c == '-')
+}
+
+/// A dependency table, e.g., `[dependencies]` or `[target.'cfg(unix)'.dependencies]`.
+#[derive(Debug, Clone)]
+pub(crate) struct DependencyTable<'a> {
+    section: &'static str,
+    target: Option<Cow<'a, str>>,
+    // The keys of this table, e.g., `["target", "cfg(unix)", "dependencies"]`.
+    path: Vec<Cow<'a, str>>,
+    dependencies: Vec<(Cow<'a, str>, Dependency<'a>)>,
+}
+
+impl DependencyTable<'_> {
+    fn into_owned(self) -> DependencyTable<'static> {
+        DependencyTable {
+            section: self.section,
+            target: self.target.map(owned),
+            path: self.path.into_iter().map(owned).collect(),
+            dependencies: self
+                .dependencies
+                .into_iter()
+                .map(|(key, dependency)| (owned(key), dependency.into_owned()))
+                .collect(),
+        }
+    }
+}
+
+/// Returns the name of the dependency table used in error messages.
+pub(crate) fn table_name(section: &str, target: Option<&str>) -> String {
+    match target {
+        Some(target) => format!("target.'{target}'.{section}"),
+        None => section.to_owned(),
+    }
+}
+
+/// All dependency tables of the manifest, including ones that are not searched,
+/// and `[workspace.dependencies]`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DependencyTables<'a> {
+    tables: Vec<DependencyTable<'a>>,
+    // `None` if the workspace is unknown.
+    pub(crate) workspace_dependencies: Option<WorkspaceDependencies<'a>>,
+    // The manifests of the path dependencies read to get their library names.
+    pub(crate) path_manifests: Vec<PathBuf>,
+    // The paths to the manifest files that were looked for to find the
+    // workspace root and the manifests of the path dependencies, including
+    // ones that don't exist or couldn't be read.
+    pub(crate) probed_paths: Vec<PathBuf>,
+}
+
+/// The dependencies in `[workspace.dependencies]` section.
+pub(crate) type WorkspaceDependencies<'a> = Vec<(Cow<'a, str>, Dependency<'a>)>;
+
+/// Deserializes the dependency tables of the specified manifest, or only
+/// `[workspace.dependencies]` if `workspace_only` is `true`.
+///
+/// `text` is the source text of the manifest, used to locate invalid entries.
+pub(crate) fn dependency_tables<'a>(
+    root: Spanned<DeTable<'a>>,
+    text: &str,
+    workspace_only: bool,
+) -> Result<DependencyTables<'a>> {
+    Table(ManifestVisitor { workspace_only })
+        .deserialize(Deserializer::from(root))
+        .map_err(|e| Source { text: Some(text), path: None }.invalid_at(&e))
+}
+
+/// The keys of `[workspace.dependencies]` section.
+const WORKSPACE_DEPENDENCIES: &[&str] = &["workspace", "dependencies"];
+
+impl<'a> DependencyTables<'a> {
+    /// Converts the strings borrowed from the source text into owned ones.
+    pub(crate) fn into_owned(self) -> DependencyTables<'static> {
+        DependencyTables {
+            tables: self.tables.into_iter().map(DependencyTable::into_owned).collect(),
+            workspace_dependencies: self.workspace_dependencies.map(into_owned),
+            path_manifests: self.path_manifests,
+            probed_paths: self.probed_paths,
+        }
+    }
+
+    /// Merges `[workspace.dependencies]` into the dependencies inherited from
+    /// the workspace.
+    pub(crate) fn inherit(&mut self) {
+        let Some(workspace_dependencies) = &self.workspace_dependencies else { return };
+        for table in &mut self.tables {
+            for (key, dependency) in &mut table.dependencies {
+                if dependency.workspace != Some(true) {
+                    continue;
+                }
+                if let Some((_, workspace)) = workspace_dependencies.iter().find(|(k, _)| k == key)
+                {
+                    *dependency = dependency.inherit(workspace);
+                }
+            }
+        }
+    }
+
+    /// Returns an iterator over the dependencies in the dependency tables.
+    pub(crate) fn dependencies_mut(&mut self) -> impl Iterator<Item = &mut Dependency<'a>> {
+        self.tables.iter_mut().flat_map(|table| table.dependencies.iter_mut().map(|(_, d)| d))
+    }
+
+    /// Returns the first problem found in the dependency tables and
+    /// `[workspace.dependencies]` that cargo would reject or warn about.
+    ///
+    /// `workspace_source` is the source of the manifest that has
+    /// `[workspace.dependencies]`.
+    pub(crate) fn validate(&self, source: Source<'_>, workspace_source: Source<'_>) -> Result<()> {
+        for table in &self.tables {
+            let section = table_name(table.section, table.target.as_deref());
+            let path: Vec<_> = table.path.iter().map(|key| &**key).collect();
+            for (key, dependency) in &table.dependencies {
+                dependency.validate(source, &section, &path, key, false)?;
+                let Some(workspace_dependencies) = &self.workspace_dependencies else { continue };
+                if dependency.workspace == Some(true)
+                    && !workspace_dependencies.iter().any(|(k, _)| k == key)
+                {
+                    return Err(source.invalid(
+                        format!(
+                            "`{key}` in [{section}] section is inherited from the workspace, \
+                             but [workspace.dependencies] section is missing `{key}`"
+                        ),
+                        &entry_path(&path, key, None),
+                    ));
+                }
+            }
+        }
+        for (key, dependency) in self.workspace_dependencies.iter().flatten() {
+            dependency.validate(
+                workspace_source,
+                "workspace.dependencies",
+                WORKSPACE_DEPENDENCIES,
+                key,
+                true,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Collects the dependencies in the order in which they are searched: the
+    /// dependency tables at the top level and the platform-specific dependency
+    /// tables that are active on the specified platform, in the order specified
+    /// by `precedence`.
+    pub(crate) fn entries(
+        &self,
+        dependencies: Dependencies,
+        platform: Option<&Platform>,
+        precedence: Precedence,
+    ) -> Vec<Entry<'_, 'a>> {
+        let sections = || dependencies.as_slice().iter().flat_map(|&section| spellings(section));
+        let top_level = sections().flat_map(|&section| {
+            self.tables.iter().filter(move |t| t.section == section && t.target.is_none())
+        });
+        let platform_specific = sections().flat_map(|&section| {
+            let mut tables: Vec<_> = self
+                .tables
+                .iter()
+                .filter(|t| {
+                    t.section == section
+                        && t.target.as_deref().is_some_and(|target| {
+                            platform.is_none_or(|platform| platform.matches(target))
+                        })
+                })
+                .collect();
+            // The order of the tables in the parsed manifest depends on whether
+            // the `preserve_order` feature of `toml` is enabled by another crate,
+            // so sort them to make the result deterministic.
+            tables.sort_by(|a, b| a.target.cmp(&b.target));
+            tables
+        });
+        let tables: Vec<_> = match precedence {
+            Precedence::TargetFirst => platform_specific.chain(top_level).collect(),
+            _ => top_level.chain(platform_specific).collect(),
+        };
+        tables
+            .into_iter()
+            .filter(|table| !self.is_ignored(table))
+            .flat_map(|table| {
+                table.dependencies.iter().map(|(key, dependency)| Entry {
+                    section: table.section,
+                    target: table.target.as_ref(),
+                    key,
+                    dependency,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the table has the legacy spelling of the section, and
+    /// the table with the hyphenated spelling for the same target exists.
+    ///
+    /// Cargo ignores the legacy spelling if both are specified.
+    fn is_ignored(&self, table: &DependencyTable<'_>) -> bool {
+        let hyphenated = match table.section {
+            "dev_dependencies" => "dev-dependencies",
+            "build_dependencies" => "build-dependencies",
+            _ => return false,
+        };
+        self.tables.iter().any(|t| t.section == hyphenated && t.target == table.target)
+    }
+}
+
+/// Converts the dependencies in `[workspace.dependencies]` into owned ones.
+pub(crate) fn into_owned(
+    dependencies: WorkspaceDependencies<'_>,
+) -> WorkspaceDependencies<'static> {
+    dependencies
+        .into_iter()
+        .map(|(key, dependency)| (owned(key), dependency.into_owned()))
+        .collect()
+}
+
+/// A dependency in the order in which it is searched.
+pub(crate) struct Entry<'s, 'a> {
+    pub(crate) section: &'static str,
+    pub(crate) target: Option<&'s Cow<'a, str>>,
+    pub(crate) key: &'s Cow<'a, str>,
+    pub(crate) dependency: &'s Dependency<'a>,
+}
+
+/// A dependency accepted by the predicate.
+pub(crate) struct Accepted<'s, 'a> {
+    /// The value of `package` key if the predicate accepted it, or `None` if
+    /// the predicate accepted the key.
+    pub(crate) package: Option<&'s Cow<'a, str>>,
+    /// The version requirement, or `*` if not specified.
+    pub(crate) version: &'s str,
+}
+
+impl<'s, 'a> Entry<'s, 'a> {
+    /// Calls the predicate with the value of `package` key (if any) and the key
+    /// of this dependency, and returns `Some` if either is accepted.
+    pub(crate) fn accepts<P>(&self, predicate: &mut P) -> Option<Accepted<'s, 'a>>
+    where
+        P: FnMut(&str, &str) -> bool,
+    {
+        let version = self.dependency.version.as_deref().unwrap_or("*");
+        let package = self.dependency.package.as_ref().filter(|name| predicate(name, version));
+        (package.is_some() || predicate(self.key, version)).then_some(Accepted { package, version })
+    }
+}
+
+/// A package found in the dependency tables.
+pub(crate) trait Found {
+    /// Returns the original name of the package.
+    fn original_name(&self) -> &str;
+
+    /// Returns the current name of the package.
+    fn name(&self) -> &str;
+}
+
+/// Converts the dependencies with `package`, and returns the first package.
+///
+/// If `unique` is `true`, all dependencies are converted, and if the crate is
+/// found under multiple names, the packages of the crate are returned as an
+/// error. Only the same crate found under different names is ambiguous, so the
+/// names of the packages with the same original name are compared.
+pub(crate) fn find<'s, 'a, T: Found>(
+    entries: &[Entry<'s, 'a>],
+    unique: bool,
+    package: impl FnMut(&Entry<'s, 'a>) -> Option<T>,
+) -> Result<Option<T>, Vec<T>> {
+    if !unique {
+        return Ok(entries.iter().find_map(package));
+    }
+    let packages: Vec<_> = entries.iter().filter_map(package).collect();
+    let ambiguous = packages.iter().find(|package| {
+        packages
+            .iter()
+            .any(|p| p.original_name() == package.original_name() && p.name() != package.name())
+    });
+    match ambiguous.map(|package| package.original_name().to_owned()) {
+        Some(original_name) => {
+            Err(packages.into_iter().filter(|p| p.original_name() == original_name).collect())
+        }
+        None => Ok(packages.into_iter().next()),
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Deserialization
+//
+// The tables are deserialized with visitors that know the section being
+// deserialized, so that errors name the offending key and section. The errors
+// are raised while deserializing the offending value, so that their span is
+// the span of the value, which is used to locate the offending key.
+//
+// The keys and the string values are borrowed from the source text unless they
+// contain escape sequences.
+
+/// Deserializes a string, borrowing it from the source text if possible.
+struct Str;
+
+impl<'de> DeserializeSeed<'de> for Str {
+    type Value = Cow<'de, str>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Str {
+    type Value = Cow<'de, str>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+    fn visit_borrowed_str<E: de::Error>(self, value: &'de str) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(value))
+    }
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(value.to_owned()))
+    }
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(value))
+    }
+}
+
+/// A visitor of a table, which handles values of other types in the same way.
+trait TableVisitor<'de>: Sized {
+    type Value;
+
+    /// Handles a value that is not a table.
+    fn other<E: de::Error>(self) -> Result<Self::Value, E>;
+
+    fn visit_str<E: de::Error>(self, _value: Cow<'de, str>) -> Result<Self::Value, E> {
+        self.other()
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error>;
+}
+
+/// Adapts [`TableVisitor`] to [`Visitor`] and [`DeserializeSeed`].
+struct Table<V>(V);
+
+impl<'de, V: TableVisitor<'de>> DeserializeSeed<'de> for Table<V> {
+    type Value = V::Value;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, V: TableVisitor<'de>> Visitor<'de> for Table<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a table")
+    }
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<V::Value, E> {
+        self.0.other()
+    }
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<V::Value, E> {
+        self.0.other()
+    }
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<V::Value, E> {
+        self.0.other()
+    }
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<V::Value, E> {
+        self.0.other()
+    }
+    fn visit_borrowed_str<E: de::Error>(self, value: &'de str) -> Result<V::Value, E> {
+        self.0.visit_str(Cow::Borrowed(value))
+    }
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<V::Value, E> {
+        self.0.visit_str(Cow::Owned(value.to_owned()))
+    }
+    fn visit_string<E: de::Error>(self, value: String) -> Result<V::Value, E> {
+        self.0.visit_str(Cow::Owned(value))
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<V::Value, A::Error> {
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        self.0.other()
+    }
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<V::Value, A::Error> {
+        // toml deserializes a datetime as a map with a private key, which is
+        // handled in the same way as other values that are not a table.
+        let first = map.next_key_seed(Str)?;
+        if first.as_deref() == Some(DATETIME_KEY) {
+            map.next_value::<IgnoredAny>()?;
+            return self.0.other();
+        }
+        self.0.visit_map(Peeked { first, map })
+    }
+}
+
+/// The key that toml uses to deserialize a datetime as a map.
+const DATETIME_KEY: &str = "$__toml_private_datetime";
+
+/// A map whose first key has already been read.
+struct Peeked<'de, A> {
+    first: Option<Cow<'de, str>>,
+    map: A,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for Peeked<'de, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        match self.first.take() {
+            Some(Cow::Borrowed(key)) => {
+                seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+            }
+            Some(Cow::Owned(key)) => seed.deserialize(StringDeserializer::new(key)).map(Some),
+            None => self.map.next_key_seed(seed),
+        }
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, A::Error> {
+        self.map.next_value_seed(seed)
+    }
+}
+
+/// The root table of the manifest.
+struct ManifestVisitor {
+    // Whether to deserialize only `[workspace.dependencies]`.
+    workspace_only: bool,
+}
+
+impl<'de> TableVisitor<'de> for ManifestVisitor {
+    type Value = DependencyTables<'de>;
+
+    fn other<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(DependencyTables::default())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut tables = DependencyTables::default();
+        while let Some(key) = map.next_key_seed(Str)? {
+            if key == "workspace" {
+                tables.workspace_dependencies = map.next_value_seed(Table(WorkspaceVisitor))?;
+                continue;
+            }
+            if self.workspace_only {
+                map.next_value::<IgnoredAny>()?;
+            } else if key == "target" {
+                tables.tables.extend(map.next_value_seed(Table(TargetVisitor))?);
+            } else if let Some(&section) = SECTIONS.iter().find(|&&s| s == key) {
+                let dependencies =
+                    map.next_value_seed(Table(DependenciesVisitor { section: section.into() }))?;
+                tables.tables.push(DependencyTable {
+                    section,
+                    target: None,
+                    path: vec![key],
+                    dependencies,
+                });
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(tables)
+    }
+}
+
+/// The `[workspace]` section. Only `[workspace.dependencies]` is deserialized.
+struct WorkspaceVisitor;
+
+impl<'de> TableVisitor<'de> for WorkspaceVisitor {
+    type Value = Option<WorkspaceDependencies<'de>>;
+
+    fn other<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut dependencies = vec![];
+        while let Some(key) = map.next_key_seed(Str)? {
+            if key == "dependencies" {
+                dependencies = map.next_value_seed(Table(DependenciesVisitor {
+                    section: "workspace.dependencies".into(),
+                }))?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(Some(dependencies))
+    }
+}
+
+/// The `[target]` section.
+struct TargetVisitor;
+
+impl<'de> TableVisitor<'de> for TargetVisitor {
+    type Value = Vec<DependencyTable<'de>>;
+
+    fn other<E: de::Error>(self) -> Result<Self::Value, E> {
+        Err(E::custom("`target` is not a table"))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut tables = vec![];
+        while let Some(name) = map.next_key_seed(Str)? {
+            let path = vec![Cow::Borrowed("target"), name.clone()];
+            tables.extend(map.next_value_seed(Table(TargetSpecVisitor { name, path }))?);
+        }
+        Ok(tables)
+    }
+}
+
+/// A platform-specific table, e.g., `[target.'cfg(unix)']`.
+///
+/// Like cargo, the platform-specific dependency tables have exactly two levels
+/// below `[target]`: the target spec, and the section of the dependency table
+/// (`[target.<spec>.<section>]`). The only exception is a target triple that
+/// contains `.` (e.g., `thumbv8m.base-none-eabi`), which is parsed as nested
+/// tables unless it is quoted. To support it, a table in a target spec is also
+/// treated as a target spec if the target spec is not a `cfg` expression and
+/// the key of the table is not a section and can be a part of a target triple.
+struct TargetSpecVisitor<'de> {
+    /// The target triple or the `cfg` expression.
+    name: Cow<'de, str>,
+    // The keys of this table, e.g., `["target", "cfg(unix)"]`.
+    path: Vec<Cow<'de, str>>,
+}
+
+impl<'de> TableVisitor<'de> for TargetSpecVisitor<'de> {
+    type Value = Vec<DependencyTable<'de>>;
+
+    fn other<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(vec![])
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut tables = vec![];
+        while let Some(key) = map.next_key_seed(Str)? {
+            let mut path = self.path.clone();
+            if let Some(&section) = SECTIONS.iter().find(|&&s| s == key) {
+                path.push(key);
+                let dependencies = map.next_value_seed(Table(DependenciesVisitor {
+                    section: table_name(section, Some(&self.name)),
+                }))?;
+                tables.push(DependencyTable {
+                    section,
+                    target: Some(self.name.clone()),
+                    path,
+                    dependencies,
+                });
+            } else if !self.name.starts_with("cfg(")
+                && key.contains('-')
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                // The rest of an unquoted target triple that contains `.`,
+                // which always contains `-` (e.g., `base-none-eabi` of
+                // `thumbv8m.base-none-eabi`).
+                let name = Cow::Owned(format!("{}.{key}", self.name));
+                path.push(key);
+                tables.extend(map.next_value_seed(Table(TargetSpecVisitor { name, path }))?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(tables)
+    }
+}
+
+/// A dependency table.
+struct DependenciesVisitor {
+    // The name of the table used in error messages.
+    section: String,
+}
+
+impl<'de> TableVisitor<'de> for DependenciesVisitor {
+    type Value = Vec<(Cow<'de, str>, Dependency<'de>)>;
+
+    fn other<E: de::Error>(self) -> Result<Self::Value, E> {
+        Err(E::custom(format!("[{}] section is not a table", self.section)))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut dependencies = vec![];
+        while let Some(key) = map.next_key_seed(Str)? {
+            let dependency = map
+                .next_value_seed(Table(DependencyVisitor { section: &self.section, key: &key }))?;
+            dependencies.push((key, dependency));
+        }
+        Ok(dependencies)
+    }
+}
+
+/// A dependency in a dependency table.
+struct DependencyVisitor<'a> {
+    section: &'a str,
+    key: &'a str,
+}
+
+impl<'de> TableVisitor<'de> for DependencyVisitor<'_> {
+    type Value = Dependency<'de>;
+
+    fn other<E: de::Error>(self) -> Result<Self::Value, E> {
+        Err(E::custom(format!(
+            "`{}` in [{}] section is not a string or a table",
+            self.key, self.section
+        )))
+    }
+
+    fn visit_str<E: de::Error>(self, version: Cow<'de, str>) -> Result<Self::Value, E> {
+        Ok(Dependency { version: Some(version), ..Dependency::default() })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let section = format!("{}.{}", self.section, self.key);
+        let mut dependency = Dependency::default();
+        // `default-features` takes precedence over the legacy `default_features`.
+        let mut legacy_default_features = None;
+        while let Some(key) = map.next_key_seed(Str)? {
+            let field = Field { section: &section, key: &key };
+            match &*key {
+                "version" => dependency.version = Some(map.next_value_seed(field.of())?),
+                "package" => dependency.package = Some(map.next_value_seed(field.of())?),
+                "features" => dependency.features = map.next_value_seed(field.of())?,
+                "default-features" => {
+                    dependency.default_features = Some(map.next_value_seed(field.of())?);
+                }
+                "default_features" => {
+                    legacy_default_features = Some(map.next_value_seed(field.of())?);
+                }
+                "optional" => dependency.optional = Some(map.next_value_seed(field.of())?),
+                "workspace" => dependency.workspace = Some(map.next_value_seed(field.of())?),
+                "git" => dependency.git = Some(map.next_value_seed(field.of())?),
+                "branch" => dependency.branch = Some(map.next_value_seed(field.of())?),
+                "tag" => dependency.tag = Some(map.next_value_seed(field.of())?),
+                "rev" => dependency.rev = Some(map.next_value_seed(field.of())?),
+                "path" => dependency.path = Some(map.next_value_seed(field.of())?),
+                "base" => dependency.base = Some(map.next_value_seed(field.of())?),
+                "registry" => dependency.registry = Some(map.next_value_seed(field.of())?),
+                "registry-index" => {
+                    dependency.registry_index = Some(map.next_value_seed(field.of())?);
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+            dependency.keys.push(key);
+        }
+        dependency.default_features = dependency.default_features.or(legacy_default_features);
+        Ok(dependency)
+    }
+}
+
+/// A field of a dependency, e.g., `version` in `foo = { version = "0.1" }`.
+#[derive(Clone, Copy)]
+struct Field<'a> {
+    section: &'a str,
+    key: &'a str,
+}
+
+impl<'a> Field<'a> {
+    /// Returns the seed that deserializes the value of this field as `T`.
+    fn of<T>(self) -> FieldSeed<'a, T> {
+        FieldSeed { field: self, ty: PhantomData }
+    }
+}
+
+struct FieldSeed<'a, T> {
+    field: Field<'a>,
+    ty: PhantomData<T>,
+}
+
+/// The value of a field of a dependency.
+enum FieldValue<'de> {
+    String(Cow<'de, str>),
+    Bool(bool),
+    Array(Vec<FieldValue<'de>>),
+    Other,
+}
+
+impl<'de> Deserialize<'de> for FieldValue<'de> {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldValueVisitor;
+
+        impl<'de> Visitor<'de> for FieldValueVisitor {
+            type Value = FieldValue<'de>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("any value")
+            }
+            fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+                Ok(FieldValue::Bool(value))
+            }
+            fn visit_i64<E: de::Error>(self, _: i64) -> Result<Self::Value, E> {
+                Ok(FieldValue::Other)
+            }
+            fn visit_u64<E: de::Error>(self, _: u64) -> Result<Self::Value, E> {
+                Ok(FieldValue::Other)
+            }
+            fn visit_f64<E: de::Error>(self, _: f64) -> Result<Self::Value, E> {
+                Ok(FieldValue::Other)
+            }
+            fn visit_borrowed_str<E: de::Error>(self, value: &'de str) -> Result<Self::Value, E> {
+                Str.visit_borrowed_str(value).map(FieldValue::String)
+            }
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Str.visit_str(value).map(FieldValue::String)
+            }
+            fn visit_string<E: de::Error>(self, value: String) -> Result<Self::Value, E> {
+                Str.visit_string(value).map(FieldValue::String)
+            }
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut values = vec![];
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(FieldValue::Array(values))
+            }
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+                Ok(FieldValue::Other)
+            }
+        }
+
+        deserializer.deserialize_any(FieldValueVisitor)
+    }
+}
+
+/// The type of a field of a dependency.
+trait FieldType<'de>: Sized {
+    /// The description of this type used in error messages.
+    const EXPECTED: &'static str;
+
+    fn from_value(value: FieldValue<'de>) -> Option<Self>;
+}
+
+impl<'de> FieldType<'de> for Cow<'de, str> {
+    const EXPECTED: &'static str = "a string";
+
+    fn from_value(value: FieldValue<'de>) -> Option<Self> {
+        match value {
+            FieldValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FieldType<'_> for bool {
+    const EXPECTED: &'static str = "a boolean";
+
+    fn from_value(value: FieldValue<'_>) -> Option<Self> {
+        match value {
+            FieldValue::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> FieldType<'de> for Vec<Cow<'de, str>> {
+    const EXPECTED: &'static str = "an array of strings";
+
+    fn from_value(value: FieldValue<'de>) -> Option<Self> {
+        match value {
+            FieldValue::Array(values) => values.into_iter().map(Cow::from_value).collect(),
+            _ => None,
+        }
+    }
+}
+
+impl<'de, T: FieldType<'de>> DeserializeSeed<'de> for FieldSeed<'_, T> {
+    type Value = T;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        T::from_value(FieldValue::deserialize(deserializer)?).ok_or_else(|| {
+            D::Error::custom(format!(
+                "`{}` in [{}] section is not {}",
+                self.field.key,
+                self.field.section,
+                T::EXPECTED
+            ))
+        })
+    }
+}
@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use alloc::{borrow::ToOwned as _, boxed::Box, string::String, vec::Vec};
+use std::env;
+
+/// The platform for which the crate is being compiled.
+///
+/// This is used to determine which platform-specific dependency tables
+/// (`[target.'cfg(...)'.dependencies]` and `[target.<triple>.dependencies]`)
+/// are active. See also [`Manifest::platform`](crate::Manifest::platform).
+///
+/// # Examples
+///
+/// ```
+/// use find_crate::Platform;
+///
+/// let mut platform = Platform::new("x86_64-unknown-linux-gnu");
+/// platform.add_cfg("unix", None);
+/// platform.add_cfg("target_os", Some("linux"));
+///
+/// assert!(platform.matches("x86_64-unknown-linux-gnu"));
+/// assert!(platform.matches("cfg(unix)"));
+/// assert!(platform.matches(r#"cfg(all(unix, target_os = "linux"))"#));
+/// assert!(!platform.matches(r#"cfg(target_os = "macos")"#));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Platform {
+    triple: String,
+    cfgs: Vec<(String, Option<String>)>,
+}
+
+impl Platform {
+    /// Creates a new `Platform` with the specified target triple and no cfgs.
+    #[must_use]
+    pub fn new(triple: &str) -> Self {
+        Self { triple: triple.to_owned(), cfgs: Vec::new() }
+    }
+
+    /// Creates a new `Platform` from the [`TARGET`] and [`CARGO_CFG_*`]
+    /// environment variables.
+    ///
+    /// Note that cargo sets these environment variables only when running build
+    /// scripts. Returns `None` if the `TARGET` environment variable is not set.
+    ///
+    /// [`TARGET`]: https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-build-scripts
+    /// [`CARGO_CFG_*`]: https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-build-scripts
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let mut platform = Self::new(&env::var("TARGET").ok()?);
+        for (key, value) in env::vars_os() {
+            let (Some(key), Some(value)) = (key.to_str(), value.to_str()) else { continue };
+            let Some(name) = key.strip_prefix("CARGO_CFG_") else { continue };
+            let name = name.to_ascii_lowercase();
+            if value.is_empty() {
+                platform.add_cfg(&name, None);
+            } else {
+                // Cfgs that have multiple values (e.g., `target_feature`) are
+                // separated by commas.
+                for value in value.split(',') {
+                    platform.add_cfg(&name, Some(value));
+                }
+            }
+        }
+        Some(platform)
+    }
+
+    /// Returns the target triple.
+    #[must_use]
+    pub fn triple(&self) -> &str {
+        &self.triple
+    }
+
+    /// Adds a cfg, such as `unix` (`name = "unix", value = None`) or
+    /// `target_os = "linux"` (`name = "target_os", value = Some("linux")`).
+    pub fn add_cfg(&mut self, name: &str, value: Option<&str>) {
+        self.cfgs.push((name.to_owned(), value.map(str::to_owned)));
+    }
+
+    /// Returns `true` if the specified target is active on this platform.
+    ///
+    /// The target is the key of the platform-specific dependency table: a
+    /// cfg expression such as `cfg(windows)`, or a target triple.
+    ///
+    /// Returns `false` if the target is an invalid cfg expression.
+    #[must_use]
+    pub fn matches(&self, target: &str) -> bool {
+        match target.strip_prefix("cfg(").and_then(|s| s.strip_suffix(')')) {
+            Some(cfg) => Cfg::parse(cfg).is_some_and(|cfg| cfg.eval(self)),
+            None => target == self.triple,
+        }
+    }
+
+    fn has_cfg(&self, name: &str, value: Option<&str>) -> bool {
+        self.cfgs.iter().any(|(n, v)| n == name && v.as_deref() == value)
+    }
+}
+
+/// A cfg expression.
+///
+/// https://doc.rust-lang.org/reference/conditional-compilation.html
+#[derive(Debug)]
+enum Cfg<'a> {
+    Bool(bool),
+    Name(&'a str),
+    KeyValue(&'a str, &'a str),
+    All(Vec<Cfg<'a>>),
+    Any(Vec<Cfg<'a>>),
+    Not(Box<Cfg<'a>>),
+}
+
+impl<'a> Cfg<'a> {
+    fn parse(s: &'a str) -> Option<Self> {
+        let mut parser = Parser { s };
+        let cfg = parser.expr()?;
+        parser.s.trim_start().is_empty().then_some(cfg)
+    }
+
+    fn eval(&self, platform: &Platform) -> bool {
+        match self {
+            Cfg::Bool(b) => *b,
+            Cfg::Name(name) => platform.has_cfg(name, None),
+            Cfg::KeyValue(name, value) => platform.has_cfg(name, Some(value)),
+            Cfg::All(cfgs) => cfgs.iter().all(|cfg| cfg.eval(platform)),
+            Cfg::Any(cfgs) => cfgs.iter().any(|cfg| cfg.eval(platform)),
+            Cfg::Not(cfg) => !cfg.eval(platform),
+        }
+    }
+}
+
+struct Parser<'a> {
+    s: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn expr(&mut self) -> Option<Cfg<'a>> {
+        let name = self.ident()?;
+        if self.eat('(') {
+            let cfg = match name {
+                "all" => Cfg::All(self.list()?),
+                "any" => Cfg::Any(self.list()?),
+                "not" => {
+                    let cfg = self.expr()?;
+                    Cfg::Not(Box::new(cfg))
+                }
+                _ => return None,
+            };
+            self.eat(')').then_some(cfg)
+        } else if self.eat('=') {
+            Some(Cfg::KeyValue(name, self.string()?))
+        } else {
+            Some(match name {
+                "true" => Cfg::Bool(true),
+                "false" => Cfg::Bool(false),
+                _ => Cfg::Name(name),
+            })
+        }
+    }
+
+    fn list(&mut self) -> Option<Vec<Cfg<'a>>> {
+        let mut list = Vec::new();
+        loop {
+            if self.peek(')') {
+                return Some(list);
+            }
+            list.push(self.expr()?);
+            if !self.eat(',') {
+                return Some(list);
+            }
+        }
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        self.s = self.s.trim_start();
+        let end = self
+            .s
+            .char_indices()
+            .find(|&(i, c)| !(c == '_' || c.is_ascii_alphabetic() || i != 0 && c.is_ascii_digit()))
+            .map_or(self.s.len(), |(i, _)| i);
+        if end == 0 {
+            return None;
+        }
+        let (ident, rest) = self.s.split_at(end);
+        self.s = rest;
+        Some(ident)
+    }
+
+    fn string(&mut self) -> Option<&'a str> {
+        let s = self.s.trim_start().strip_prefix('"')?;
+        let (string, rest) = s.split_once('"')?;
+        self.s = rest;
+        Some(string)
+    }
+
+    fn peek(&mut self, c: char) -> bool {
+        self.s = self.s.trim_start();
+        self.s.starts_with(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek(c) {
+            self.s = &self.s[c.len_utf8()..];
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use alloc::string::String;
+use std::{fs, path::Path};
+
+use toml::value::{Table, Value};
+
+/// The kind of the target being compiled.
+///
+/// See [`Manifest::target_kind`](crate::Manifest::target_kind) for how this is
+/// determined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TargetKind {
+    /// The library target (`src/lib.rs` or `[lib]`), including its unit tests.
+    Lib,
+    /// A binary target (`src/main.rs`, `src/bin/*.rs`, or `[[bin]]`).
+    Bin,
+    /// An integration test target (`tests/*.rs` or `[[test]]`).
+    Test,
+    /// An example target (`examples/*.rs` or `[[example]]`).
+    Example,
+    /// A benchmark target (`benches/*.rs` or `[[bench]]`).
+    Bench,
+    /// The build script (`build.rs`).
+    BuildScript,
+    /// A doctest.
+    Doctest,
+    /// The target could not be determined because the environment variables
+    /// set by cargo were not found.
+    Unknown,
+}
+
+impl TargetKind {
+    /// Returns `true` if the package is referred to by `crate` in this target,
+    /// rather than by its name as an external crate.
+    ///
+    /// If the target is unknown, the library is assumed to be compiled.
+    pub(crate) fn is_lib(self) -> bool {
+        matches!(self, TargetKind::Lib | TargetKind::Unknown)
+    }
+}
+
+pub(crate) fn target_kind<F>(
+    manifest: &Table,
+    manifest_dir: Option<&Path>,
+    lib_name: Option<&str>,
+    mut env: F,
+) -> TargetKind
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    // rustdoc sets this environment variable when compiling doctests.
+    if env("UNSTABLE_RUSTDOC_TEST_PATH").is_some() {
+        return TargetKind::Doctest;
+    }
+    // https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-crates
+    let Some(crate_name) = env("CARGO_CRATE_NAME") else { return TargetKind::Unknown };
+    if crate_name.starts_with("build_script_") {
+        return TargetKind::BuildScript;
+    }
+    // Cargo sets this only when compiling binaries, including executable
+    // examples.
+    if env("CARGO_BIN_NAME").is_some() {
+        return if has_target(
+            manifest,
+            manifest_dir,
+            "example",
+            "autoexamples",
+            "examples",
+            &crate_name,
+        ) {
+            TargetKind::Example
+        } else {
+            TargetKind::Bin
+        };
+    }
+    // Cargo sets this only when compiling integration tests and benchmarks.
+    if env("CARGO_TARGET_TMPDIR").is_some() {
+        return if has_target(manifest, manifest_dir, "bench", "autobenches", "benches", &crate_name)
+        {
+            TargetKind::Bench
+        } else {
+            TargetKind::Test
+        };
+    }
+    if lib_name == Some(&*crate_name) {
+        return TargetKind::Lib;
+    }
+    // The remaining target kind is an example of library type
+    // (`crate-type = ["lib"]`).
+    TargetKind::Example
+}
+
+/// Returns `true` if the package has a target of the specified kind with the
+/// specified crate name, declared in the manifest (e.g., `[[bench]]`) or
+/// discovered automatically from the directory (e.g., `benches/*.rs`).
+fn has_target(
+    manifest: &Table,
+    manifest_dir: Option<&Path>,
+    kind: &str,
+    auto: &str,
+    dir: &str,
+    crate_name: &str,
+) -> bool {
+    fn eq(name: &str, crate_name: &str) -> bool {
+        name.replace('-', "_") == crate_name
+    }
+
+    let declared = manifest.get(kind).and_then(Value::as_array).is_some_and(|targets| {
+        targets
+            .iter()
+            .any(|t| t.get("name").and_then(Value::as_str).is_some_and(|n| eq(n, crate_name)))
+    });
+    if declared {
+        return true;
+    }
+
+    // https://doc.rust-lang.org/cargo/reference/cargo-targets.html#target-auto-discovery
+    let auto = manifest.get("package").and_then(|p| p.get(auto)?.as_bool()).unwrap_or(true);
+    let Some(manifest_dir) = manifest_dir.filter(|_| auto) else { return false };
+    let Ok(entries) = fs::read_dir(manifest_dir.join(dir)) else { return false };
+    entries.filter_map(Result::ok).any(|entry| {
+        let path = entry.path();
+        let name = if path.is_dir() {
+            path.join("main.rs").is_file().then(|| path.file_name()).flatten()
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            path.file_stem()
+        } else {
+            None
+        };
+        name.and_then(|name| name.to_str()).is_some_and(|name| eq(name, crate_name))
+    })
+}
@@ -2,11 +2,37 @@
 
 #![allow(clippy::needless_raw_string_hashes)]
 
-use std::str::FromStr as _;
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    str::FromStr as _,
+};
 
-use find_crate::{Dependencies, Manifest};
+use find_crate::{
+    Dependencies, DependencyKind, DependencyKinds, DependencySource, Error, FoundCrate,
+    GitReference, Lockfile, Manifest, ManifestRef, Package, Platform, Precedence, TargetKind,
+};
 use semver::{Version, VersionReq};
 
+/// Returns the path to the specified file or directory in `tests/fixtures`.
+fn fixture(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(path)
+}
+
+/// Asserts that the manifest fails to parse or validate with the expected reason,
+/// both as `Manifest` and as `ManifestRef`.
+#[track_caller]
+fn assert_invalid(manifest: &str, expected: &str) {
+    match Manifest::from_str(manifest).and_then(|manifest| manifest.validate()) {
+        Err(Error::InvalidManifest(reason, _)) => assert_eq!(reason, expected),
+        res => panic!("{res:?}"),
+    }
+    match ManifestRef::parse(manifest).and_then(|manifest| manifest.validate()) {
+        Err(Error::InvalidManifest(reason, _)) => assert_eq!(reason, expected),
+        res => panic!("{res:?}"),
+    }
+}
+
 #[test]
 fn dependencies() {
     const MANIFEST: &str = r#"
@@ -49,6 +75,30 @@ fn dependencies() {
     assert_eq!("0.2", manifest.find(|s| s == NAME2).unwrap().version);
 
     assert_eq!(None, manifest.find(|s| s == NAME3));
+
+    manifest.dependencies = Dependencies::Kinds(DependencyKinds::DEV | DependencyKinds::BUILD);
+    assert_eq!("0.1.1", manifest.find(|s| s == NAME1).unwrap().version);
+    assert_eq!("0.2", manifest.find(|s| s == NAME2).unwrap().version);
+
+    manifest.dependencies = DependencyKinds::empty().into();
+    assert_eq!(None, manifest.find(|s| s == NAME1));
+}
+
+#[test]
+fn dependency_kinds() {
+    let mut kinds = DependencyKinds::empty();
+    assert!(kinds.is_empty());
+    kinds |= DependencyKind::Normal.into();
+    kinds.insert(DependencyKinds::BUILD);
+    assert!(kinds.contains(DependencyKinds::NORMAL | DependencyKinds::BUILD));
+    assert!(!kinds.contains(DependencyKinds::DEV));
+    kinds.remove(DependencyKinds::NORMAL);
+    assert_eq!(kinds, DependencyKinds::BUILD);
+    assert!(DependencyKinds::ALL.contains(kinds));
+
+    assert_eq!(Dependencies::Default.kinds(), DependencyKinds::NORMAL | DependencyKinds::DEV);
+    assert_eq!(Dependencies::All.kinds(), DependencyKinds::ALL);
+    assert_eq!(Dependencies::Kinds(kinds).kinds(), kinds);
 }
 
 #[test]
@@ -110,6 +160,256 @@ fn target() {
     assert_eq!("0.1", manifest.find(|s| s == NAME4).unwrap().version);
 }
 
+#[test]
+fn features() {
+    const MANIFEST: &str = r#"
+        [dependencies]
+        foo = "0.1"
+        bar = { version = "0.2", features = ["std", "serde"], default-features = false }
+        baz = { version = "0.3", optional = true, default_features = false }
+    "#;
+
+    let manifest = Manifest::from_str(MANIFEST).unwrap();
+
+    let package = manifest.find(|s| s == "foo").unwrap();
+    assert!(package.features().is_empty());
+    assert!(package.default_features());
+    assert!(!package.optional());
+
+    let package = manifest.find(|s| s == "bar").unwrap();
+    assert_eq!(package.features(), ["std", "serde"]);
+    assert!(!package.default_features());
+    assert!(!package.optional());
+
+    let package = manifest.find(|s| s == "baz").unwrap();
+    assert!(package.features().is_empty());
+    assert!(!package.default_features());
+    assert!(package.optional());
+
+    // Features are additive with the features from [workspace.dependencies].
+    let path = fixture("workspace/member/Cargo.toml");
+    let manifest = Manifest::from_path(&path).unwrap();
+    let package = manifest.find(|s| s == "bar-core").unwrap();
+    assert_eq!(package.features(), ["std", "serde"]);
+
+    // `optional` is not inherited from [workspace.dependencies].
+    let manifest = Manifest::from_str(
+        "[workspace.dependencies]\nfoo = { version = \"1\", optional = true }\n\
+         [dependencies]\nfoo.workspace = true",
+    )
+    .unwrap();
+    assert!(!manifest.find(|s| s == "foo").unwrap().optional());
+}
+
+#[test]
+fn source() {
+    const MANIFEST: &str = r#"
+        [dependencies]
+        foo = "0.1"
+        bar = { version = "0.2", registry = "my-registry" }
+        baz = { path = "../baz", version = "0.3" }
+        qux = { git = "https://github.com/user/qux" }
+        quux = { git = "https://github.com/user/quux", branch = "dev" }
+        corge = { git = "https://github.com/user/corge", tag = "v0.1.0" }
+        grault = { git = "https://github.com/user/grault", rev = "0123abc" }
+        garply = { version = "0.4", registry-index = "sparse+https://example.com/index/" }
+        waldo = { path = "waldo", base = "my-base" }
+    "#;
+
+    fn is_git(source: &DependencySource, url: &str, reference: Option<&GitReference>) -> bool {
+        match source {
+            DependencySource::Git { url: u, reference: r, .. } => {
+                u == url && r.as_ref() == reference
+            }
+            _ => false,
+        }
+    }
+
+    let manifest = Manifest::from_str(MANIFEST).unwrap();
+    let source = |name: &str| manifest.find(|s| s == name).unwrap().source().unwrap().clone();
+
+    assert_eq!(DependencySource::Registry(None), source("foo"));
+    assert_eq!(DependencySource::Registry(Some("my-registry".to_owned())), source("bar"));
+    assert_eq!(DependencySource::Path(PathBuf::from("../baz")), source("baz"));
+    assert_eq!(
+        DependencySource::RegistryIndex("sparse+https://example.com/index/".to_owned()),
+        source("garply")
+    );
+    // Path bases other than `workspace` are defined in the cargo configuration.
+    assert_eq!(DependencySource::Path(PathBuf::from("waldo")), source("waldo"));
+    assert!(is_git(&source("qux"), "https://github.com/user/qux", None));
+    assert!(is_git(
+        &source("quux"),
+        "https://github.com/user/quux",
+        Some(&GitReference::Branch("dev".to_owned()))
+    ));
+    assert!(is_git(
+        &source("corge"),
+        "https://github.com/user/corge",
+        Some(&GitReference::Tag("v0.1.0".to_owned()))
+    ));
+    assert!(is_git(
+        &source("grault"),
+        "https://github.com/user/grault",
+        Some(&GitReference::Rev("0123abc".to_owned()))
+    ));
+
+    // The path of the dependency inherited from the workspace is relative to
+    // the workspace root.
+    let root = fixture("workspace");
+    let mut manifest = Manifest::from_path(&root.join("member/Cargo.toml")).unwrap();
+    assert_eq!(
+        Some(&DependencySource::Path(root.join("baz"))),
+        manifest.find(|s| s == "baz").unwrap().source()
+    );
+    // So is the path with `base = "workspace"`.
+    manifest.dependencies = Dependencies::Build;
+    assert_eq!(
+        Some(&DependencySource::Path(root.join("baz"))),
+        manifest.find(|s| s == "baz").unwrap().source()
+    );
+
+    let manifest = Manifest::from_str("[package]\nname = \"foo\"").unwrap();
+    assert_eq!(None, manifest.crate_package().unwrap().source());
+}
+
+#[test]
+fn find_all() {
+    const MANIFEST: &str = r#"
+        [dependencies]
+        foo = "0.1"
+
+        [dev-dependencies]
+        foo2 = { package = "foo", version = "0.1.1" }
+
+        [build-dependencies]
+        foo = "0.1.2"
+
+        [target.'cfg(windows)'.dependencies]
+        foo = "0.1.3"
+
+        [target.thumbv8m.base-none-eabi.dev-dependencies]
+        foo = "0.1.4"
+    "#;
+
+    const NAME1: &str = "foo";
+    const NAME2: &str = "bar";
+
+    let mut manifest = Manifest::from_str(MANIFEST).unwrap();
+
+    let packages: Vec<_> = manifest.find_all(|s| s == NAME1).collect();
+    assert_eq!(packages.len(), 4);
+    assert_eq!(packages.iter().map(|p| (&*p.name, &*p.version)).collect::<Vec<_>>(), [
+        ("foo", "0.1"),
+        ("foo2", "0.1.1"),
+        ("foo", "0.1.3"),
+        ("foo", "0.1.4")
+    ]);
+    assert_eq!(packages.iter().map(|p| (p.section(), p.target())).collect::<Vec<_>>(), [
+        ("dependencies", None),
+        ("dev-dependencies", None),
+        ("dependencies", Some("cfg(windows)")),
+        ("dev-dependencies", Some("thumbv8m.base-none-eabi")),
+    ]);
+    assert_eq!(packages[0], manifest.find(|s| s == NAME1).unwrap());
+    assert_eq!(packages.iter().map(Package::kind).collect::<Vec<_>>(), [
+        Some(DependencyKind::Normal),
+        Some(DependencyKind::Dev),
+        Some(DependencyKind::Normal),
+        Some(DependencyKind::Dev),
+    ]);
+
+    manifest.dependencies = Dependencies::Build;
+    let packages: Vec<_> = manifest.find_all(|s| s == NAME1).collect();
+    assert_eq!(packages.len(), 1);
+    assert_eq!("0.1.2", packages[0].version);
+    assert_eq!("build-dependencies", packages[0].section());
+    assert_eq!(Some(DependencyKind::Build), packages[0].kind());
+
+    assert_eq!(manifest.find_all(|s| s == NAME2).count(), 0);
+}
+
+#[test]
+fn platform() {
+    const MANIFEST: &str = r#"
+        [target.'cfg(target_os = "linux")'.dependencies]
+        foo = "0.1"
+
+        [target.'cfg(target_os = "macos")'.dependencies]
+        foo = "0.2"
+
+        [target.'cfg(all(unix, not(target_os = "macos")))'.dependencies]
+        bar = "0.1"
+
+        [target.'cfg(any(windows, target_feature = "sse2"))'.dependencies]
+        baz = "0.1"
+
+        [target.x86_64-unknown-linux-gnu.dependencies]
+        qux = "0.1"
+
+        [target.thumbv8m.base-none-eabi.dependencies]
+        qux = "0.2"
+    "#;
+
+    let mut manifest = Manifest::from_str(MANIFEST).unwrap();
+
+    assert_eq!(manifest.find_all(|s| s == "foo").count(), 2);
+
+    let mut linux = Platform::new("x86_64-unknown-linux-gnu");
+    linux.add_cfg("unix", None);
+    linux.add_cfg("target_os", Some("linux"));
+    linux.add_cfg("target_feature", Some("fxsr"));
+    linux.add_cfg("target_feature", Some("sse2"));
+    manifest.platform = Some(linux);
+    assert_eq!("0.1", manifest.find(|s| s == "foo").unwrap().version);
+    assert_eq!(manifest.find_all(|s| s == "foo").count(), 1);
+    assert!(manifest.find(|s| s == "bar").is_some());
+    assert!(manifest.find(|s| s == "baz").is_some());
+    assert_eq!("0.1", manifest.find(|s| s == "qux").unwrap().version);
+
+    let mut macos = Platform::new("aarch64-apple-darwin");
+    macos.add_cfg("unix", None);
+    macos.add_cfg("target_os", Some("macos"));
+    manifest.platform = Some(macos);
+    assert_eq!("0.2", manifest.find(|s| s == "foo").unwrap().version);
+    assert_eq!(None, manifest.find(|s| s == "bar"));
+    assert_eq!(None, manifest.find(|s| s == "baz"));
+    assert_eq!(None, manifest.find(|s| s == "qux"));
+
+    manifest.platform = Some(Platform::new("thumbv8m.base-none-eabi"));
+    assert_eq!(None, manifest.find(|s| s == "foo"));
+    assert_eq!("0.2", manifest.find(|s| s == "qux").unwrap().version);
+}
+
+#[test]
+fn cfg() {
+    let mut platform = Platform::new("x86_64-pc-windows-msvc");
+    platform.add_cfg("windows", None);
+    platform.add_cfg("target_os", Some("windows"));
+
+    assert!(platform.matches("x86_64-pc-windows-msvc"));
+    assert!(!platform.matches("x86_64-unknown-linux-gnu"));
+    assert!(platform.matches("cfg(windows)"));
+    assert!(platform.matches("cfg( windows )"));
+    assert!(!platform.matches("cfg(unix)"));
+    assert!(platform.matches("cfg(true)"));
+    assert!(!platform.matches("cfg(false)"));
+    assert!(platform.matches(r#"cfg(target_os = "windows")"#));
+    assert!(!platform.matches(r#"cfg(target_os = "linux")"#));
+    assert!(platform.matches("cfg(not(unix))"));
+    assert!(platform.matches("cfg(all())"));
+    assert!(!platform.matches("cfg(any())"));
+    assert!(platform.matches(r#"cfg(all(windows, target_os = "windows",))"#));
+    assert!(platform.matches(r#"cfg(any(unix, all(windows, not(target_os = "linux"))))"#));
+
+    // Invalid cfg expressions.
+    assert!(!platform.matches("cfg(windows"));
+    assert!(!platform.matches("cfg(not(windows, unix))"));
+    assert!(!platform.matches("cfg(target_os = windows)"));
+    assert!(!platform.matches("cfg(windows unix)"));
+    assert!(!platform.matches("cfg(foo(windows))"));
+}
+
 #[test]
 fn workspace() {
     const MANIFEST: &str = r#"
@@ -127,6 +427,52 @@ fn workspace() {
     assert_eq!("*", manifest.find(|s| s == NAME1).unwrap().version);
 }
 
+#[test]
+fn workspace_inherited() {
+    const MANIFEST: &str = r#"
+        [workspace.dependencies]
+        foo = "0.1"
+        bar = { package = "bar-core", version = "0.2" }
+
+        [dependencies]
+        foo.workspace = true
+        bar.workspace = true
+        baz.workspace = true
+    "#;
+
+    const NAME1: &str = "foo";
+    const NAME2: &str = "bar-core";
+    const NAME3: &str = "baz";
+
+    let manifest = Manifest::from_str(MANIFEST).unwrap();
+
+    assert_eq!(NAME1, manifest.find(|s| s == NAME1).unwrap().name);
+    assert_eq!("0.1", manifest.find(|s| s == NAME1).unwrap().version);
+
+    assert_eq!("bar", manifest.find(|s| s == NAME2).unwrap().name);
+    assert_eq!("0.2", manifest.find(|s| s == NAME2).unwrap().version);
+
+    // Not found in [workspace.dependencies].
+    assert_eq!(NAME3, manifest.find(|s| s == NAME3).unwrap().name);
+    assert_eq!("*", manifest.find(|s| s == NAME3).unwrap().version);
+
+    let path = fixture("workspace/member/Cargo.toml");
+    let manifest = Manifest::from_path(&path).unwrap();
+
+    assert_eq!(NAME1, manifest.find(|s| s == NAME1).unwrap().name);
+    assert_eq!("0.1", manifest.find(|s| s == NAME1).unwrap().version);
+
+    let package = manifest.find(|s| s == NAME2).unwrap();
+    assert_eq!("bar", package.name);
+    assert_eq!("0.2", package.version);
+    assert_eq!(NAME2, package.original_name());
+    assert!(!package.is_original());
+
+    // The name of the library target of the path dependency.
+    assert_eq!("baz_lib", manifest.find(|s| s == NAME3).unwrap().name);
+    assert_eq!("*", manifest.find(|s| s == NAME3).unwrap().version);
+}
+
 #[test]
 fn find2() {
     fn check(req: &str, version: &Version) -> bool {
@@ -157,6 +503,196 @@ fn find2() {
     assert_eq!("*", manifest.find2(|s, v| s == NAME3 && check(v, &version)).unwrap().version);
 }
 
+#[cfg(feature = "semver")]
+#[test]
+fn find_version() {
+    const MANIFEST: &str = r#"
+        [dependencies]
+        foo = "0.1"
+        bar = "~0.2.1"
+        baz = { path = ".." }
+        qux = ">=1.2, <1.5"
+        quux = "1.*"
+
+        [dev-dependencies]
+        foo2 = { package = "foo", version = "0.3" }
+    "#;
+
+    let manifest = Manifest::from_str(MANIFEST).unwrap();
+    let version = |v| Version::parse(v).unwrap();
+
+    assert_eq!("foo", manifest.find_version("foo", &version("0.1.5")).unwrap().name);
+    assert_eq!("foo2", manifest.find_version("foo", &version("0.3.0")).unwrap().name);
+    assert_eq!(None, manifest.find_version("foo", &version("0.2.0")));
+
+    assert!(manifest.find_version("bar", &version("0.2.9")).is_some());
+    assert_eq!(None, manifest.find_version("bar", &version("0.2.0")));
+    assert_eq!(None, manifest.find_version("bar", &version("0.3.0")));
+
+    assert!(manifest.find_version("baz", &version("10.0.0")).is_some());
+
+    assert!(manifest.find_version("qux", &version("1.4.9")).is_some());
+    assert_eq!(None, manifest.find_version("qux", &version("1.5.0")));
+
+    assert!(manifest.find_version("quux", &version("1.9.0")).is_some());
+    assert_eq!(None, manifest.find_version("quux", &version("2.0.0")));
+
+    let package = manifest.find(|s| s == "bar").unwrap();
+    assert_eq!(Some(VersionReq::parse("~0.2.1").unwrap()), package.version_req());
+    let package = manifest.find(|s| s == "baz").unwrap();
+    assert_eq!(Some(VersionReq::STAR), package.version_req());
+}
+
+#[cfg(feature = "proc-macro2")]
+#[test]
+fn to_path_tokens() {
+    const MANIFEST: &str = r#"
+        [package]
+        name = "crate-name"
+
+        [dependencies]
+        foo = "0.1"
+        bar-renamed = { package = "bar", version = "0.2" }
+    "#;
+
+    let manifest = Manifest::from_str(MANIFEST).unwrap();
+
+    let path = manifest.find(|s| s == "foo").unwrap().to_path_tokens();
+    assert_eq!(":: foo", path.to_string());
+    let path = manifest.find(|s| s == "bar").unwrap().to_path_tokens();
+    assert_eq!(":: bar_renamed", path.to_string());
+    let path = manifest.crate_package().unwrap().to_path_tokens();
+    assert_eq!("crate", path.to_string());
+}
+
+#[test]
+fn find_or_itself() {
+    const MANIFEST: &str = r#"
+        [package]
+        name = "crate-name"
+
+        [dependencies]
+        foo = "0.1"
+    "#;
+
+    let manifest = Manifest::from_str(MANIFEST).unwrap();
+
+    // CARGO_CRATE_NAME is not set at runtime, so the library is assumed to be compiled.
+    assert_eq!(Some(FoundCrate::Itself), manifest.find_or_itself(|s| s == "crate-name"));
+    assert_eq!(
+        Some(FoundCrate::Named(manifest.find(|s| s == "foo").unwrap())),
+        manifest.find_or_itself(|s| s == "foo")
+    );
+    assert_eq!(None, manifest.find_or_itself(|s| s == "bar"));
+
+    let manifest = Manifest::from_str("[dependencies]\nfoo = \"0.1\"").unwrap();
+    assert!(matches!(manifest.find_or_itself(|s| s == "foo"), Some(FoundCrate::Named(..))));
+}
+
+#[test]
+fn target_kind() {
+    const MANIFEST: &str = r#"
+        [package]
+        name = "crate-name"
+
+        [[bench]]
+        name = "my-bench"
+        harness = false
+
+        [[example]]
+        name = "my-example"
+    "#;
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl FnMut(&str) -> Option<String> + 'a {
+        move |key| vars.iter().find(|(k, _)| *k == key).map(|(_, v)| (*v).to_owned())
+    }
+
+    let manifest = Manifest::from_str(MANIFEST).unwrap();
+
+    // CARGO_CRATE_NAME is not set at runtime.
+    assert_eq!(TargetKind::Unknown, manifest.target_kind());
+    assert_eq!(TargetKind::Unknown, manifest.target_kind_with(env(&[])));
+
+    assert_eq!(
+        TargetKind::Lib,
+        manifest.target_kind_with(env(&[("CARGO_CRATE_NAME", "crate_name")]))
+    );
+    assert_eq!(
+        TargetKind::Bin,
+        manifest.target_kind_with(env(&[
+            ("CARGO_CRATE_NAME", "crate_name"),
+            ("CARGO_BIN_NAME", "crate-name")
+        ]))
+    );
+    assert_eq!(
+        TargetKind::Test,
+        manifest.target_kind_with(env(&[
+            ("CARGO_CRATE_NAME", "test"),
+            ("CARGO_TARGET_TMPDIR", "/tmp")
+        ]))
+    );
+    assert_eq!(
+        TargetKind::Bench,
+        manifest.target_kind_with(env(&[
+            ("CARGO_CRATE_NAME", "my_bench"),
+            ("CARGO_TARGET_TMPDIR", "/tmp")
+        ]))
+    );
+    assert_eq!(
+        TargetKind::Example,
+        manifest.target_kind_with(env(&[
+            ("CARGO_CRATE_NAME", "my_example"),
+            ("CARGO_BIN_NAME", "my-example")
+        ]))
+    );
+    assert_eq!(
+        TargetKind::Bin,
+        manifest
+            .target_kind_with(env(&[("CARGO_CRATE_NAME", "my_bin"), ("CARGO_BIN_NAME", "my-bin")]))
+    );
+    // Examples of library type are not binaries.
+    assert_eq!(
+        TargetKind::Example,
+        manifest.target_kind_with(env(&[("CARGO_CRATE_NAME", "my_example")]))
+    );
+    assert_eq!(
+        TargetKind::BuildScript,
+        manifest.target_kind_with(env(&[("CARGO_CRATE_NAME", "build_script_build")]))
+    );
+    assert_eq!(
+        TargetKind::Doctest,
+        manifest.target_kind_with(env(&[
+            ("CARGO_CRATE_NAME", "crate_name"),
+            ("UNSTABLE_RUSTDOC_TEST_PATH", "src/lib.rs")
+        ]))
+    );
+
+    // Benchmarks and examples discovered automatically.
+    let path = fixture("workspace/member/Cargo.toml");
+    let manifest = Manifest::from_path(&path).unwrap();
+    assert_eq!(
+        TargetKind::Bench,
+        manifest.target_kind_with(env(&[
+            ("CARGO_CRATE_NAME", "auto_bench"),
+            ("CARGO_TARGET_TMPDIR", "/tmp")
+        ]))
+    );
+    assert_eq!(
+        TargetKind::Test,
+        manifest.target_kind_with(env(&[
+            ("CARGO_CRATE_NAME", "my_bench"),
+            ("CARGO_TARGET_TMPDIR", "/tmp")
+        ]))
+    );
+    assert_eq!(
+        TargetKind::Example,
+        manifest.target_kind_with(env(&[
+            ("CARGO_CRATE_NAME", "auto_example"),
+            ("CARGO_BIN_NAME", "auto-example")
+        ]))
+    );
+}
+
 #[test]
 fn crate_name() {
     const MANIFEST: &str = r#"
@@ -169,6 +705,9 @@ fn crate_name() {
     let package = manifest.crate_package().unwrap();
     assert_eq!("crate_name", package.name);
     assert_eq!("0.1.0", package.version);
+    assert_eq!("package", package.section());
+    assert_eq!(None, package.kind());
+    assert_eq!(None, package.target());
 
     let manifest = r#"
     [package]
@@ -179,3 +718,716 @@ fn crate_name() {
     assert_eq!("crate_name", package.name);
     assert_eq!("0.0.0", package.version);
 }
+
+#[test]
+fn crate_version_inherited() {
+    const MANIFEST: &str = r#"
+    [workspace.package]
+    version = "0.2.0"
+
+    [package]
+    name = "crate-name"
+    version.workspace = true
+    "#;
+
+    let manifest = Manifest::from_str(MANIFEST).unwrap();
+    let package = manifest.crate_package().unwrap();
+    assert_eq!("crate_name", package.name);
+    assert_eq!("0.2.0", package.version);
+
+    let path = fixture("workspace/member/Cargo.toml");
+    let manifest = Manifest::from_path(&path).unwrap();
+    let package = manifest.crate_package().unwrap();
+    assert_eq!("member", package.name);
+    assert_eq!("0.2.0", package.version);
+
+    // The workspace root manifest is unknown.
+    let manifest = r#"
+    [package]
+    name = "crate-name"
+    version.workspace = true
+    "#;
+    let manifest = Manifest::from_str(manifest).unwrap();
+    assert!(matches!(manifest.crate_package(), Err(Error::InvalidManifest(..))));
+}
+
+#[test]
+fn lockfile() {
+    const LOCKFILE: &str = r#"
+        [[package]]
+        name = "foo"
+        version = "0.1.0"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [metadata]
+        "checksum foo 0.1.0 (registry+https://github.com/rust-lang/crates.io-index)" = "abc"
+    "#;
+
+    let path = fixture("workspace/member/Cargo.toml");
+    let manifest = Manifest::from_path(&path).unwrap();
+    let lockfile = manifest.lockfile().unwrap();
+    assert_eq!(lockfile.packages().len(), 5);
+
+    let package = manifest.find(|s| s == "foo").unwrap();
+    let locked = lockfile.resolve(&package).unwrap();
+    assert_eq!("foo", locked.name());
+    assert_eq!("0.1.5", locked.version());
+    assert_eq!(Some("registry+https://github.com/rust-lang/crates.io-index"), locked.source());
+    assert_eq!(
+        Some("0000000000000000000000000000000000000000000000000000000000000001"),
+        locked.checksum()
+    );
+
+    let package = manifest.find(|s| s == "bar-core").unwrap();
+    let locked = lockfile.resolve(&package).unwrap();
+    assert_eq!("bar-core", locked.name());
+    assert_eq!("0.2.3", locked.version());
+
+    let package = manifest.find(|s| s == "baz").unwrap();
+    let locked = lockfile.resolve(&package).unwrap();
+    assert_eq!("0.1.0", locked.version());
+    assert_eq!(None, locked.source());
+    assert_eq!(None, locked.checksum());
+
+    // Without the current package, multiple versions cannot be disambiguated.
+    let lockfile =
+        Lockfile::from_path(&path.parent().unwrap().parent().unwrap().join("Cargo.lock")).unwrap();
+    let package = manifest.find(|s| s == "foo").unwrap();
+    assert_eq!(None, lockfile.resolve(&package));
+
+    // Lockfile version 1.
+    let lockfile = Lockfile::from_str(LOCKFILE).unwrap();
+    assert_eq!(Some("abc"), lockfile.resolve(&package).unwrap().checksum());
+
+    let manifest = Manifest::from_str("").unwrap();
+    assert!(matches!(manifest.lockfile(), Err(Error::NotFoundLockfile)));
+    assert!(matches!(
+        Lockfile::from_str("[[package]]\nname = \"foo\""),
+        Err(Error::InvalidLockfile(..))
+    ));
+}
+
+#[test]
+fn lib_name() {
+    const MANIFEST: &str = r#"
+    [package]
+    name = "my-pkg"
+
+    [lib]
+    name = "mylib"
+    "#;
+
+    let manifest = Manifest::from_str(MANIFEST).unwrap();
+    let package = manifest.crate_package().unwrap();
+    assert_eq!("mylib", package.name);
+    assert_eq!("my-pkg", package.original_name());
+
+    // Renamed path dependency uses the key as crate name.
+    let root = fixture("workspace");
+    let manifest = format!(
+        "[dependencies]\nbaz = {{ path = {:?} }}\nbaz2 = {{ path = {:?}, package = \"baz\" }}",
+        root.join("baz"),
+        root.join("baz"),
+    );
+    let manifest = Manifest::from_str(&manifest).unwrap();
+    let packages: Vec<_> = manifest.find_all(|s| s == "baz").collect();
+    assert_eq!(packages.len(), 2);
+    assert_eq!("baz_lib", packages[0].name);
+    assert_eq!("baz2", packages[1].name);
+}
+
+#[test]
+fn cache() {
+    let manifest_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let uncached = Manifest::from_path(&manifest_path).unwrap();
+    let mut manifest = Manifest::new().unwrap();
+    assert_eq!(uncached.crate_package().unwrap(), manifest.crate_package().unwrap());
+    assert_eq!(uncached.find(|s| s == "toml"), manifest.find(|s| s == "toml"));
+
+    // Changes to the returned manifest don't affect the cached manifest.
+    manifest.dependencies = Dependencies::Build;
+    assert!(manifest.find(|s| s == "toml").is_none());
+    assert!(Manifest::new().unwrap().find(|s| s == "toml").is_some());
+}
+
+#[test]
+fn tracked_paths() {
+    let root = fixture("workspace");
+    let manifest = Manifest::from_path(&root.join("member/Cargo.toml")).unwrap();
+    assert_eq!(manifest.tracked_paths(), [
+        root.join("member/Cargo.toml"),
+        root.join("Cargo.toml"),
+        root.join("baz/Cargo.toml"),
+        root.join("Cargo.lock"),
+    ]);
+    let manifest = Manifest::from_path(&root.join("Cargo.toml")).unwrap();
+    assert_eq!(manifest.tracked_paths(), [root.join("Cargo.toml"), root.join("Cargo.lock")]);
+
+    let manifest = Manifest::from_str("[package]\nname = \"foo\"").unwrap();
+    assert!(manifest.tracked_paths().is_empty());
+}
+
+#[test]
+fn workspace_search_stops_at_target_package() {
+    // A package verified by `cargo package` is not a member of the workspace
+    // that contains the target directory.
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("workspace-search");
+    let package_dir = root.join("target/package/foo-0.1.0");
+    std::fs::create_dir_all(&package_dir).unwrap();
+    std::fs::write(root.join("Cargo.toml"), "[workspace]\n[workspace.dependencies]\nbar = \"1\"")
+        .unwrap();
+    std::fs::write(package_dir.join("Cargo.toml"), "[dependencies]\nbar.workspace = true").unwrap();
+    let manifest = Manifest::from_path(&package_dir.join("Cargo.toml")).unwrap();
+    assert_eq!(manifest.tracked_paths(), [package_dir.join("Cargo.toml")]);
+    assert_eq!(manifest.find(|s| s == "bar").unwrap().version, "*");
+}
+
+#[cfg(feature = "proc-macro2")]
+#[test]
+fn to_tracking_tokens() {
+    let root = fixture("workspace");
+    let manifest = Manifest::from_path(&root.join("member/Cargo.toml")).unwrap();
+    let expected: Vec<_> = manifest
+        .tracked_paths()
+        .iter()
+        .map(|path| format!("const _ : & [u8] = include_bytes ! ({path:?}) ;"))
+        .collect();
+    assert_eq!(manifest.to_tracking_tokens().to_string(), expected.join(" "));
+}
+
+#[test]
+fn manifest_ref() {
+    const MANIFEST: &str = r#"
+        [package]
+        name = "my-pkg"
+        version = "0.1.0"
+
+        [workspace.dependencies]
+        bar = { package = "bar-core", version = "0.2" }
+
+        [dependencies]
+        foo-bar = "0.1"
+        baz = { package = "baz-core", version = "0.3" }
+        bar = { workspace = true, features = ["std"] }
+
+        [dev-dependencies]
+        qux = { path = "qux" }
+
+        [target.'cfg(unix)'.dependencies]
+        unix-only = "1"
+
+        [target.x86_64-unknown-linux-gnu.build-dependencies]
+        linux-only = "2"
+    "#;
+    const NAMES: &[&str] = &[
+        "foo-bar",
+        "baz",
+        "baz-core",
+        "bar",
+        "bar-core",
+        "qux",
+        "unix-only",
+        "linux-only",
+        "none",
+    ];
+
+    let mut manifest = Manifest::from_str(MANIFEST).unwrap();
+    let mut manifest_ref = ManifestRef::parse(MANIFEST).unwrap();
+    let mut platform = Platform::new("x86_64-unknown-linux-gnu");
+    platform.add_cfg("unix", None);
+    for dependencies in [Dependencies::Default, Dependencies::Dev, Dependencies::All] {
+        for platform in
+            [None, Some(Platform::new("x86_64-pc-windows-msvc")), Some(platform.clone())]
+        {
+            manifest.dependencies = dependencies;
+            manifest_ref.dependencies = dependencies;
+            manifest.platform.clone_from(&platform);
+            manifest_ref.platform = platform;
+            for name in NAMES {
+                let package = manifest.find(|s| s == *name);
+                let package_ref = manifest_ref.find(|s| s == *name);
+                assert_eq!(package.is_some(), package_ref.is_some(), "{name}");
+                let (Some(package), Some(package_ref)) = (package, package_ref) else { continue };
+                assert_eq!(package.name, package_ref.name);
+                assert_eq!(package.version, package_ref.version);
+                assert_eq!(package.original_name(), package_ref.original_name());
+                assert_eq!(package.is_original(), package_ref.is_original());
+                assert_eq!(package.section(), package_ref.section());
+                assert_eq!(package.kind(), package_ref.kind());
+                assert_eq!(package.target(), package_ref.target());
+            }
+        }
+    }
+
+    // Only the package that needs to replace `-` allocates the name.
+    let manifest_ref = ManifestRef::parse(MANIFEST).unwrap();
+    assert!(matches!(manifest_ref.find(|s| s == "baz").unwrap().name, Cow::Borrowed("baz")));
+    assert!(matches!(manifest_ref.find(|s| s == "foo-bar").unwrap().name, Cow::Owned(_)));
+
+    // The returned packages borrow from the source text, not from the manifest.
+    let package = ManifestRef::parse(MANIFEST).unwrap().find(|s| s == "baz-core").unwrap();
+    assert!(matches!(package.version, Cow::Borrowed("0.3")));
+    assert_eq!(package.original_name(), "baz-core");
+    // Strings that contain escape sequences are allocated.
+    let manifest_ref = ManifestRef::parse("[dependencies]\nfoo = \"\\u0030.1\"").unwrap();
+    assert!(
+        matches!(manifest_ref.find(|s| s == "foo").unwrap().version, Cow::Owned(ref v) if v == "0.1")
+    );
+
+    assert!(matches!(ManifestRef::parse("[dependencies"), Err(Error::Toml(..))));
+}
+
+#[test]
+fn invalid_dependencies() {
+    assert_invalid(
+        "[dependencies]\nfoo = 5",
+        "`foo` in [dependencies] section is not a string or a table",
+    );
+    assert_invalid(
+        "[dev-dependencies]\nfoo = { package = 3 }",
+        "`package` in [dev-dependencies.foo] section is not a string",
+    );
+    assert_invalid(
+        "[build-dependencies]\nfoo = { version = \"1\", optional = \"yes\" }",
+        "`optional` in [build-dependencies.foo] section is not a boolean",
+    );
+    assert_invalid(
+        "[dependencies]\nfoo = { version = \"1\", features = [\"a\", 1] }",
+        "`features` in [dependencies.foo] section is not an array of strings",
+    );
+    assert_invalid(
+        "[target.'cfg(unix)'.dependencies]\nfoo = true",
+        "`foo` in [target.'cfg(unix)'.dependencies] section is not a string or a table",
+    );
+    assert_invalid(
+        "[dependencies]\nfoo = 1979-05-27",
+        "`foo` in [dependencies] section is not a string or a table",
+    );
+    assert_invalid(
+        "[dependencies]\nfoo = { version = 1979-05-27 }",
+        "`version` in [dependencies.foo] section is not a string",
+    );
+    assert_invalid("dependencies = 1979-05-27", "[dependencies] section is not a table");
+    assert_invalid(
+        "[workspace.dependencies]\nfoo = { git = 1 }",
+        "`git` in [workspace.dependencies.foo] section is not a string",
+    );
+    assert_invalid("dependencies = 1", "[dependencies] section is not a table");
+    assert_invalid("target = 1", "`target` is not a table");
+
+    // Unknown keys are ignored.
+    let manifest = Manifest::from_str("[dependencies]\nfoo = { version = \"1\", unknown = 1 }");
+    assert_eq!("1", manifest.unwrap().find(|s| s == "foo").unwrap().version);
+}
+
+#[test]
+fn validate() {
+    Manifest::new().unwrap().validate().unwrap();
+    let root = fixture("workspace");
+    Manifest::from_path(&root.join("member/Cargo.toml")).unwrap().validate().unwrap();
+    // Like cargo, non-ASCII identifiers are valid crate names.
+    Manifest::from_str("[dependencies]\n\"föo\" = \"1\"").unwrap().validate().unwrap();
+
+    assert_invalid(
+        "[dependencies]\nfoo = { version = \"1\", feature = [\"std\"] }",
+        "unknown key `feature` in [dependencies.foo] section",
+    );
+    assert_invalid(
+        "[dependencies]\n\"foo bar\" = \"1\"",
+        "`foo bar` in [dependencies] section is not a valid crate name",
+    );
+    assert_invalid(
+        "[dev-dependencies]\nfoo = { package = \"1foo\", version = \"1\" }",
+        "`package` in [dev-dependencies.foo] section is not a valid crate name: `1foo`",
+    );
+    assert_invalid(
+        "[dependencies]\n\"foo.bar\" = \"1\"",
+        "`foo.bar` in [dependencies] section is not a valid crate name",
+    );
+    assert_invalid(
+        "[workspace.dependencies]\nfoo = \"1\"\n[dependencies]\nfoo = { workspace = true, path = \"foo\" }",
+        "`path` cannot be specified with `workspace = true` in [dependencies.foo] section",
+    );
+    assert_invalid(
+        "[target.'cfg(unix)'.dependencies]\nfoo = { workspace = false }",
+        "`workspace` in [target.'cfg(unix)'.dependencies.foo] section cannot be false",
+    );
+    assert_invalid(
+        "[workspace.dependencies]\nfoo = { workspace = true }",
+        "`workspace` cannot be specified in [workspace.dependencies.foo] section",
+    );
+    assert_invalid(
+        "[workspace.dependencies]\nfoo = { version = \"1\", optional = true }",
+        "`optional` cannot be true in [workspace.dependencies.foo] section",
+    );
+    // toml's private key of datetimes is not reported as an unknown key.
+    assert_invalid(
+        "[dependencies]\nfoo = { version = \"1\", date = 1979-05-27 }",
+        "unknown key `date` in [dependencies.foo] section",
+    );
+    assert!(matches!(
+        Manifest::from_str("[dependencies]\nfoo = 1979-05-27"),
+        Err(Error::InvalidManifest(reason, _)) if !reason.contains("$__toml_private_datetime")
+    ));
+    assert_invalid(
+        "[workspace]\n[dependencies]\nfoo = { workspace = true }",
+        "`foo` in [dependencies] section is inherited from the workspace, \
+         but [workspace.dependencies] section is missing `foo`",
+    );
+
+    // The workspace is unknown.
+    Manifest::from_str("[dependencies]\nfoo = { workspace = true }").unwrap().validate().unwrap();
+}
+
+#[test]
+fn location() {
+    #[track_caller]
+    fn assert_location(err: &Error, path: Option<&Path>, line: usize, column: usize) {
+        let location = err.location().unwrap();
+        assert_eq!(location.path(), path);
+        assert_eq!((location.line(), location.column()), (line, column));
+    }
+
+    let manifest = "[package]\nname = \"foo\"\n\n[target.'cfg(unix)'.dependencies]\nbar = 5\n";
+    assert_location(&Manifest::from_str(manifest).unwrap_err(), None, 5, 1);
+    assert_location(&ManifestRef::parse(manifest).unwrap_err(), None, 5, 1);
+
+    let manifest = "[dependencies]\nfoo = { version = \"1\", feature = [\"std\"] }";
+    let err = ManifestRef::parse(manifest).unwrap().validate().unwrap_err();
+    assert_location(&err, None, 2, 24);
+    // The source text of `Manifest::from_str` is not kept.
+    let err = Manifest::from_str(manifest).unwrap().validate().unwrap_err();
+    assert!(err.location().is_none());
+
+    let manifest_path = fixture("invalid/Cargo.toml");
+    let err = Manifest::from_path(&manifest_path).unwrap_err();
+    assert_location(&err, Some(&manifest_path), 6, 24);
+    assert_eq!(err.location().unwrap().to_string(), format!("{}:6:24", manifest_path.display()));
+
+    // The workspace root manifest is invalid.
+    let root = fixture("invalid-workspace");
+    let err = Manifest::from_path(&root.join("member/Cargo.toml")).unwrap_err();
+    assert!(matches!(err, Error::Toml(..)));
+    assert_eq!(err.location().unwrap().path(), Some(&*root.join("Cargo.toml")));
+
+    let err = Manifest::from_str("[dependencies]\nfoo = ").unwrap_err();
+    assert!(matches!(err, Error::Toml(..)));
+    assert_location(&err, None, 2, 7);
+
+    assert!(Error::NotFoundManifestDir.location().is_none());
+}
+
+#[test]
+fn not_found() {
+    const MANIFEST: &str = r#"
+        [dependencies]
+        foo_core = "0.1"
+        baz = "0.2"
+
+        [build-dependencies]
+        cc = "1"
+
+        [target.'cfg(windows)'.dependencies]
+        winapi = "0.3"
+    "#;
+
+    let mut manifest = Manifest::from_str(MANIFEST).unwrap();
+    let mut platform = Platform::new("x86_64-unknown-linux-gnu");
+    platform.add_cfg("unix", None);
+    manifest.platform = Some(platform);
+    let not_found = |res| match res {
+        Err(Error::NotFound(e)) => e,
+        res => panic!("{res:?}"),
+    };
+
+    let e = not_found(manifest.try_find(|s| s == "foo-core"));
+    assert_eq!(e.sections(), ["dependencies", "dev-dependencies"]);
+    assert_eq!(e.similar_names(), ["foo_core"]);
+    assert!(e.filtered().is_empty());
+    // The predicate is called with a bounded number of variants of each name.
+    let mut calls = 0;
+    let e = not_found(manifest.try_find(|s| {
+        calls += 1;
+        s == "bazz"
+    }));
+    assert_eq!(e.similar_names(), ["baz"]);
+    assert!(calls < 1000, "{calls}");
+
+    let e = not_found(manifest.try_find(|s| s == "cc" || s == "winapi"));
+    assert!(e.similar_names().is_empty());
+    let filtered: Vec<_> =
+        e.filtered().iter().map(|p| (p.original_name(), p.section(), p.target())).collect();
+    assert_eq!(filtered, [
+        ("cc", "build-dependencies", None),
+        ("winapi", "dependencies", Some("cfg(windows)"))
+    ]);
+    assert_eq!(
+        Error::NotFound(e).to_string(),
+        "the crate with the specified name not found in dependencies \
+         (searched: [dependencies], [dev-dependencies]); \
+         filtered out: `cc` (version `1`) in [build-dependencies], \
+         `winapi` (version `0.3`) in [target.'cfg(windows)'.dependencies]"
+    );
+
+    // Version mismatch.
+    let e = not_found(manifest.try_find2(|s, v| s == "baz" && (v == "*" || v == "0.3")));
+    assert_eq!(e.filtered().len(), 1);
+    assert_eq!(e.filtered()[0].version, "0.2");
+
+    assert_eq!(manifest.try_find(|s| s == "baz").unwrap().version, "0.2");
+}
+
+#[test]
+fn legacy_sections() {
+    const MANIFEST: &str = r#"
+        [dev_dependencies]
+        foo = "0.1"
+
+        [build-dependencies]
+        bar = "0.2"
+
+        [build_dependencies]
+        bar = "0.1"
+        baz = "0.3"
+
+        [target.'cfg(unix)'.dev_dependencies]
+        qux = "0.4"
+
+        [target.'cfg(unix)'.build-dependencies]
+        quux = "0.5"
+
+        [target.'cfg(unix)'.build_dependencies]
+        corge = "0.6"
+    "#;
+
+    let mut manifest = Manifest::from_str(MANIFEST).unwrap();
+    let manifest_ref = ManifestRef::parse(MANIFEST).unwrap();
+    let package = manifest.find(|s| s == "foo").unwrap();
+    assert_eq!(package.section(), "dev_dependencies");
+    assert_eq!(package.kind(), Some(DependencyKind::Dev));
+    assert_eq!(manifest_ref.find(|s| s == "foo").unwrap().section(), "dev_dependencies");
+    let package = manifest.find(|s| s == "qux").unwrap();
+    assert_eq!((package.section(), package.target()), ("dev_dependencies", Some("cfg(unix)")));
+    assert_eq!(manifest_ref.find(|s| s == "qux").unwrap().kind(), Some(DependencyKind::Dev));
+    assert_eq!(None, manifest.find(|s| s == "baz"));
+
+    manifest.dependencies = Dependencies::Build;
+    // The legacy spelling is ignored if the hyphenated spelling is also
+    // specified, at the top level or for the same target.
+    let package = manifest.find(|s| s == "bar").unwrap();
+    assert_eq!((package.section(), package.version.as_str()), ("build-dependencies", "0.2"));
+    assert_eq!(None, manifest.find(|s| s == "baz"));
+    assert_eq!(None, manifest_ref.find(|s| s == "baz"));
+    let package = manifest.find(|s| s == "quux").unwrap();
+    assert_eq!(
+        (package.section(), package.kind()),
+        ("build-dependencies", Some(DependencyKind::Build))
+    );
+    assert_eq!(None, manifest.find(|s| s == "corge"));
+    assert_eq!(None, manifest.find(|s| s == "foo"));
+
+    let packages: Vec<_> =
+        manifest.find_all(|s| s == "bar").map(|p| p.section().to_owned()).collect();
+    assert_eq!(packages, ["build-dependencies"]);
+
+    // The legacy spelling is searched if only it is specified for the target.
+    let mut manifest = Manifest::from_str(
+        r#"
+        [build-dependencies]
+        bar = "0.2"
+
+        [target.'cfg(unix)'.build_dependencies]
+        baz = "0.3"
+        "#,
+    )
+    .unwrap();
+    manifest.dependencies = Dependencies::Build;
+    let package = manifest.find(|s| s == "baz").unwrap();
+    assert_eq!(
+        (package.section(), package.kind()),
+        ("build_dependencies", Some(DependencyKind::Build))
+    );
+
+    assert!(matches!(
+        Manifest::from_str("[dev_dependencies]\nfoo = 1"),
+        Err(Error::InvalidManifest(reason, _))
+            if reason == "`foo` in [dev_dependencies] section is not a string or a table"
+    ));
+}
+
+#[test]
+fn precedence() {
+    const MANIFEST: &str = r#"
+        [target.'cfg(windows)'.dependencies]
+        foo-windows = { package = "foo", version = "0.1" }
+
+        [target.'cfg(unix)'.dependencies]
+        foo-unix = { package = "foo", version = "0.1" }
+
+        [dependencies]
+        foo = "0.1"
+        bar = "0.2"
+        baz-core = "0.3"
+
+        [target.'cfg(unix)'.dev-dependencies]
+        bar = { version = "0.2", features = ["std"] }
+    "#;
+
+    let mut manifest = Manifest::from_str(MANIFEST).unwrap();
+    let mut manifest_ref = ManifestRef::parse(MANIFEST).unwrap();
+    assert_eq!(manifest.precedence, Precedence::TopLevelFirst);
+    assert_eq!(manifest.find(|s| s == "foo").unwrap().name, "foo");
+    // Platform-specific dependency tables are searched in the order of their
+    // target names, not in the order in which they appear.
+    let names: Vec<_> = manifest.find_all(|s| s == "foo").map(|p| p.name).collect();
+    assert_eq!(names, ["foo", "foo_unix", "foo_windows"]);
+
+    manifest.precedence = Precedence::TargetFirst;
+    manifest_ref.precedence = Precedence::TargetFirst;
+    assert_eq!(manifest.find(|s| s == "foo").unwrap().name, "foo_unix");
+    assert_eq!(manifest_ref.find(|s| s == "foo").unwrap().name, "foo_unix");
+    let mut platform = Platform::new("x86_64-pc-windows-msvc");
+    platform.add_cfg("windows", None);
+    manifest.platform = Some(platform);
+    assert_eq!(manifest.find(|s| s == "foo").unwrap().name, "foo_windows");
+    manifest.platform = None;
+
+    manifest.precedence = Precedence::Unique;
+    manifest_ref.precedence = Precedence::Unique;
+    assert_eq!(manifest.find(|s| s == "foo"), None);
+    assert_eq!(manifest_ref.find(|s| s == "foo"), None);
+    match manifest.try_find(|s| s == "foo") {
+        Err(e @ Error::Ambiguous(_)) => assert_eq!(
+            e.to_string(),
+            "the crate with the specified name found under multiple names: \
+             `foo` in [dependencies], `foo_unix` in [target.'cfg(unix)'.dependencies], \
+             `foo_windows` in [target.'cfg(windows)'.dependencies]"
+        ),
+        res => panic!("{res:?}"),
+    }
+    // The crate found in multiple tables under the same name is not ambiguous.
+    manifest.dependencies = Dependencies::All;
+    assert_eq!(manifest.try_find(|s| s == "bar").unwrap().section(), "dependencies");
+    assert!(manifest_ref.find(|s| s == "bar").is_some());
+    // Different crates accepted by the predicate are not ambiguous.
+    assert_eq!(manifest.try_find(|s| s == "bar" || s == "baz-core").unwrap().name, "bar");
+    assert_eq!(manifest_ref.find(|s| s == "bar" || s == "baz-core").unwrap().name, "bar");
+    // Only the packages of the crate found under multiple names are reported.
+    match manifest.try_find(|s| s == "bar" || s == "foo") {
+        Err(Error::Ambiguous(packages)) => {
+            let names: Vec<_> = packages.iter().map(|p| &*p.name).collect();
+            assert_eq!(names, ["foo", "foo_unix", "foo_windows"]);
+        }
+        res => panic!("{res:?}"),
+    }
+    assert_eq!(manifest_ref.find(|s| s == "bar" || s == "foo"), None);
+}
+
+#[test]
+fn find_unique() {
+    const MANIFEST: &str = r#"
+        [dependencies]
+        foo = "0.1"
+        bar = "0.2"
+        foo-core = "0.3"
+
+        [dev-dependencies]
+        foo2 = { package = "foo", version = "0.1" }
+        bar = { version = "0.2", features = ["std"] }
+    "#;
+
+    let mut manifest = Manifest::from_str(MANIFEST).unwrap();
+    assert_eq!(manifest.find(|s| s == "foo").unwrap().name, "foo");
+    match manifest.find_unique(|s| s == "foo") {
+        Err(Error::Ambiguous(packages)) => {
+            let names: Vec<_> = packages.iter().map(|p| (&*p.name, p.section())).collect();
+            assert_eq!(names, [("foo", "dependencies"), ("foo2", "dev-dependencies")]);
+        }
+        res => panic!("{res:?}"),
+    }
+    assert_eq!(manifest.find_unique(|s| s == "bar").unwrap().version, "0.2");
+    assert!(matches!(manifest.find_unique(|s| s == "baz"), Err(Error::NotFound(_))));
+
+    // Only the packages of the crate found under multiple names are reported.
+    match manifest.find_unique(|s| s == "foo" || s == "foo-core") {
+        Err(Error::Ambiguous(packages)) => {
+            let names: Vec<_> = packages.iter().map(|p| &*p.name).collect();
+            assert_eq!(names, ["foo", "foo2"]);
+        }
+        res => panic!("{res:?}"),
+    }
+
+    manifest.dependencies = Dependencies::Release;
+    assert_eq!(manifest.find_unique(|s| s == "foo").unwrap().name, "foo");
+    // Different crates accepted by the predicate are not ambiguous.
+    assert_eq!(manifest.find_unique(|s| s == "foo" || s == "foo-core").unwrap().name, "foo");
+}
+
+#[test]
+fn target_tables() {
+    #[track_caller]
+    fn assert_found(manifest: &str, expected: &[(&str, &str)]) {
+        let mut manifest_ref = ManifestRef::parse(manifest).unwrap();
+        manifest_ref.dependencies = Dependencies::All;
+        let mut manifest = Manifest::from_str(manifest).unwrap();
+        manifest.dependencies = Dependencies::All;
+        let found: Vec<_> = manifest
+            .find_all(|s| s == "foo")
+            .map(|p| (p.target().unwrap().to_owned(), p.section().to_owned()))
+            .collect();
+        let found: Vec<_> = found.iter().map(|(t, s)| (t.as_str(), s.as_str())).collect();
+        assert_eq!(found, expected);
+        let found = manifest_ref.find(|s| s == "foo");
+        assert_eq!(
+            found.as_ref().map(|p| (p.target().unwrap(), p.section())),
+            expected.first().copied()
+        );
+    }
+
+    // Target specs.
+    assert_found("[target.'cfg(unix)'.dependencies]\nfoo = \"1\"", &[(
+        "cfg(unix)",
+        "dependencies",
+    )]);
+    assert_found("[target.x86_64-unknown-linux-gnu.dev-dependencies]\nfoo = \"1\"", &[(
+        "x86_64-unknown-linux-gnu",
+        "dev-dependencies",
+    )]);
+    assert_found("[target.'thumbv8m.base-none-eabi'.build-dependencies]\nfoo = \"1\"", &[(
+        "thumbv8m.base-none-eabi",
+        "build-dependencies",
+    )]);
+    assert_found("[target.x86_64-unknown-linux-gnu.dev_dependencies]\nfoo = \"1\"", &[(
+        "x86_64-unknown-linux-gnu",
+        "dev_dependencies",
+    )]);
+    // Target triples that contain "." are parsed as nested tables unless quoted.
+    assert_found("[target.thumbv8m.base-none-eabi.dependencies]\nfoo = \"1\"", &[(
+        "thumbv8m.base-none-eabi",
+        "dependencies",
+    )]);
+    assert_found(
+        "[target.thumbv8m.dependencies]\nfoo = \"1\"\n\
+         [target.thumbv8m.base-none-eabi.dependencies]\nfoo = \"1\"",
+        &[("thumbv8m", "dependencies"), ("thumbv8m.base-none-eabi", "dependencies")],
+    );
+
+    // Tables that are not dependency tables.
+    assert_found("[target.x.dependencies.bar.dependencies]\nfoo = \"1\"", &[]);
+    assert_found("[target.x.dev-dependencies.bar.build-dependencies]\nfoo = \"1\"", &[]);
+    assert_found("[target.x.dependencies]\nbar = { dependencies = { foo = \"1\" } }", &[]);
+    assert_found("[target.'cfg(unix)'.x.dependencies]\nfoo = \"1\"", &[]);
+    assert_found("[target.x.'cfg(unix)'.dependencies]\nfoo = \"1\"", &[]);
+    assert_found("[target.x.foo]\nversion = \"1\"", &[]);
+    assert_found("[target.x86_64-unknown-linux-gnu.foo.dependencies]\nfoo = \"1\"", &[]);
+    assert_found("[target.x86_64-unknown-linux-gnu.2.17.dependencies]\nfoo = \"1\"", &[]);
+    // Unquoted target specs are joined only if the part after `.` contains `-`,
+    // so the path to a custom target specification file needs to be quoted.
+    assert_found("[target.my-target.json.dependencies]\nfoo = \"1\"", &[]);
+    assert_found("[target.'my-target.json'.dependencies]\nfoo = \"1\"", &[(
+        "my-target.json",
+        "dependencies",
+    )]);
+    assert_found("[target.dependencies]\nfoo = \"1\"", &[]);
+    assert_found("[target]\nx = 1", &[]);
+}